@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    context::Context,
+    context::{Context, Overloaded, ReadTimeout, ResponseTooLarge},
     failpoint::fail_point,
     metrics::metrics,
     param::{AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam},
@@ -28,7 +28,7 @@ use move_deps::move_core_types::{
     value::MoveValue,
 };
 use std::convert::TryInto;
-use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+use warp::{filters::BoxedFilter, http::StatusCode, Filter, Rejection, Reply};
 
 // GET /accounts/<address>
 pub fn get_account(context: Context) -> BoxedFilter<(impl Reply,)> {
@@ -80,7 +80,9 @@ async fn handle_get_account_resources(
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_resources")?;
-    Ok(Account::new(ledger_version, address, context)?.resources()?)
+    Ok(Account::new(ledger_version, address, context)?
+        .resources()
+        .await?)
 }
 
 async fn handle_get_account_modules(
@@ -89,7 +91,9 @@ async fn handle_get_account_modules(
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_modules")?;
-    Ok(Account::new(ledger_version, address, context)?.modules()?)
+    Ok(Account::new(ledger_version, address, context)?
+        .modules()
+        .await?)
 }
 
 pub(crate) struct Account {
@@ -147,18 +151,20 @@ impl Account {
         Response::new(self.latest_ledger_info, &account_data)
     }
 
-    pub fn resources(self) -> Result<impl Reply, Error> {
+    pub async fn resources(self) -> Result<impl Reply, Error> {
+        let account_state = self.account_state_with_timeout().await?;
         let resources = self
             .context
             .move_resolver()?
             .as_converter(self.context.db.clone())
-            .try_into_resources(self.account_state()?.get_resources())?;
+            .try_into_resources(account_state.get_resources())?;
         Response::new(self.latest_ledger_info, &resources)
     }
 
-    pub fn modules(self) -> Result<impl Reply, Error> {
+    pub async fn modules(self) -> Result<impl Reply, Error> {
         let modules = self
-            .account_state()?
+            .account_state_with_timeout()
+            .await?
             .into_modules()
             .map(MoveModuleBytecode::new)
             .map(|m| m.try_parse_abi())
@@ -212,9 +218,37 @@ impl Account {
     fn account_state(&self) -> Result<AccountState, Error> {
         let state = self
             .context
-            .get_account_state(self.address.into(), self.ledger_version)?
-            .ok_or_else(|| self.account_not_found())?;
-        Ok(state)
+            .get_account_state(self.address.into(), self.ledger_version);
+        self.account_state_or_error(state)
+    }
+
+    /// Same as `account_state`, with the scan bounded by `Context::read_with_timeout`
+    async fn account_state_with_timeout(&self) -> Result<AccountState, Error> {
+        let state = self
+            .context
+            .get_state_values_with_timeout(self.address.into(), self.ledger_version)
+            .await
+            .and_then(|state_values| AccountState::from_access_paths_and_values(&state_values));
+        self.account_state_or_error(state)
+    }
+
+    fn account_state_or_error(
+        &self,
+        state: Result<Option<AccountState>>,
+    ) -> Result<AccountState, Error> {
+        state
+            .map_err(|e| {
+                if e.is::<ResponseTooLarge>() {
+                    Error::bad_request(e)
+                } else if e.is::<ReadTimeout>() {
+                    Error::from_anyhow_error(StatusCode::REQUEST_TIMEOUT, e)
+                } else if e.is::<Overloaded>() {
+                    Error::from_anyhow_error(StatusCode::SERVICE_UNAVAILABLE, e)
+                } else {
+                    e.into()
+                }
+            })?
+            .ok_or_else(|| self.account_not_found())
     }
 
     fn account_not_found(&self) -> Error {
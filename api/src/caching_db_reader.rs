@@ -0,0 +1,467 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_types::{
+    account_address::AccountAddress,
+    contract_event::{ContractEvent, EventWithVersion},
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    event::EventKey,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{AccumulatorConsistencyProof, SparseMerkleProof, TransactionAccumulatorSummary},
+    state_proof::StateProof,
+    state_store::{
+        state_key::StateKey,
+        state_key_prefix::StateKeyPrefix,
+        state_value::{StateValue, StateValueChunkWithProof},
+        table::{TableHandle, TableInfo},
+    },
+    transaction::{
+        AccountTransactionsWithProof, TransactionInfo, TransactionListWithProof,
+        TransactionOutputListWithProof, TransactionWithProof, Version,
+    },
+    write_set::WriteSet,
+};
+use lru::LruCache;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use storage_interface::{DbReader, ExecutedTrees, Order, StartupInfo};
+
+/// A `DbReader` decorator caching the reads that never change once the versions they're about
+/// are committed: transactions with their infos, events and write sets, read without proofs, and
+/// accumulator root hashes. Every other read, in particular of the latest ledger state, passes
+/// through to the wrapped reader. Reads carrying proofs aren't cached, their proofs are relative
+/// to the ledger version they're read at, which changes with every commit.
+///
+/// Cached entries aren't evicted when their version is pruned, so they may still be served after
+/// that.
+pub struct CachingDbReader {
+    inner: Arc<dyn DbReader>,
+    transactions: Mutex<LruCache<Version, TransactionData>>,
+    accumulator_root_hashes: Mutex<LruCache<Version, HashValue>>,
+    // The latest version of the ledger infos read through this reader, versions up to it are
+    // committed and can be cached
+    latest_version: AtomicU64,
+}
+
+type TransactionData = (Transaction, TransactionInfo, Vec<ContractEvent>, WriteSet);
+
+impl CachingDbReader {
+    /// Caches up to `capacity` entries of each kind of read
+    pub fn new(inner: Arc<dyn DbReader>, capacity: usize) -> Self {
+        Self {
+            inner,
+            transactions: Mutex::new(LruCache::new(capacity)),
+            accumulator_root_hashes: Mutex::new(LruCache::new(capacity)),
+            latest_version: AtomicU64::new(0),
+        }
+    }
+
+    fn is_committed(&self, version: Version) -> bool {
+        version <= self.latest_version.load(Ordering::Relaxed)
+    }
+
+    fn observe(&self, ledger_info: &LedgerInfoWithSignatures) {
+        self.latest_version
+            .fetch_max(ledger_info.ledger_info().version(), Ordering::Relaxed);
+    }
+}
+
+impl DbReader for CachingDbReader {
+    fn get_transactions_without_proof(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Vec<TransactionData>> {
+        let end_version = start_version
+            .saturating_add(limit)
+            .min(ledger_version.saturating_add(1));
+        // Served from the cache only if all of the page is, the cached entries hold events
+        let cached: Option<Vec<_>> = {
+            let mut cache = self.transactions.lock();
+            (start_version..end_version)
+                .map(|version| cache.get(&version).cloned())
+                .collect()
+        };
+        if let Some(mut cached) = cached.filter(|cached| !cached.is_empty()) {
+            if !fetch_events {
+                cached
+                    .iter_mut()
+                    .for_each(|(_, _, events, _)| events.clear());
+            }
+            return Ok(cached);
+        }
+
+        let txns = self.inner.get_transactions_without_proof(
+            start_version,
+            limit,
+            ledger_version,
+            fetch_events,
+        )?;
+        if fetch_events {
+            let mut cache = self.transactions.lock();
+            for (version, txn) in (start_version..).zip(&txns) {
+                if self.is_committed(version) {
+                    cache.put(version, txn.clone());
+                }
+            }
+        }
+        Ok(txns)
+    }
+
+    fn get_accumulator_root_hash(&self, version: Version) -> Result<HashValue> {
+        if let Some(hash) = self.accumulator_root_hashes.lock().get(&version) {
+            return Ok(*hash);
+        }
+        let hash = self.inner.get_accumulator_root_hash(version)?;
+        if self.is_committed(version) {
+            self.accumulator_root_hashes.lock().put(version, hash);
+        }
+        Ok(hash)
+    }
+
+    fn get_transaction_by_version(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionWithProof> {
+        self.inner
+            .get_transaction_by_version(version, ledger_version, fetch_events)
+    }
+
+    fn get_block_timestamp(&self, version: u64) -> Result<u64> {
+        self.inner.get_block_timestamp(version)
+    }
+
+    fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<EpochChangeProof> {
+        self.inner
+            .get_epoch_ending_ledger_infos(start_epoch, end_epoch)
+    }
+
+    fn get_transactions(
+        &self,
+        start_version: Version,
+        batch_size: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionListWithProof> {
+        self.inner
+            .get_transactions(start_version, batch_size, ledger_version, fetch_events)
+    }
+
+    fn get_transactions_by_versions(
+        &self,
+        versions: &[Version],
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Vec<TransactionData>> {
+        self.inner
+            .get_transactions_by_versions(versions, ledger_version, fetch_events)
+    }
+
+    fn get_transaction_infos(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<TransactionInfo>> {
+        self.inner
+            .get_transaction_infos(start_version, limit, ledger_version)
+    }
+
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        self.inner
+            .get_transaction_by_hash(hash, ledger_version, fetch_events)
+    }
+
+    fn get_transaction_versions_by_hashes(
+        &self,
+        hashes: &[HashValue],
+        ledger_version: Version,
+    ) -> Result<Vec<Option<Version>>> {
+        self.inner
+            .get_transaction_versions_by_hashes(hashes, ledger_version)
+    }
+
+    fn get_first_txn_version(&self) -> Result<Option<Version>> {
+        self.inner.get_first_txn_version()
+    }
+
+    fn get_first_write_set_version(&self) -> Result<Option<Version>> {
+        self.inner.get_first_write_set_version()
+    }
+
+    fn get_transaction_outputs(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<TransactionOutputListWithProof> {
+        self.inner
+            .get_transaction_outputs(start_version, limit, ledger_version)
+    }
+
+    fn get_write_sets(
+        &self,
+        start_version: Version,
+        end_version: Version,
+    ) -> Result<Vec<WriteSet>> {
+        self.inner.get_write_sets(start_version, end_version)
+    }
+
+    fn get_events(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        order: Order,
+        limit: u64,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.inner.get_events(event_key, start, order, limit)
+    }
+
+    fn get_block_boundaries(&self, version: u64, latest_ledger_version: u64) -> Result<(u64, u64)> {
+        self.inner
+            .get_block_boundaries(version, latest_ledger_version)
+    }
+
+    fn get_last_version_before_timestamp(
+        &self,
+        timestamp: u64,
+        ledger_version: Version,
+    ) -> Result<Version> {
+        self.inner
+            .get_last_version_before_timestamp(timestamp, ledger_version)
+    }
+
+    fn get_latest_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>> {
+        self.inner.get_latest_state_value(state_key)
+    }
+
+    fn get_latest_epoch_state(&self) -> Result<EpochState> {
+        self.inner.get_latest_epoch_state()
+    }
+
+    fn get_state_values_by_key_prefix(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        version: Version,
+    ) -> Result<HashMap<StateKey, StateValue>> {
+        self.inner
+            .get_state_values_by_key_prefix(key_prefix, version)
+    }
+
+    fn get_state_values_by_key_prefix_from(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        version: Version,
+        limit: usize,
+    ) -> Result<Vec<(StateKey, StateValue)>> {
+        self.inner
+            .get_state_values_by_key_prefix_from(key_prefix, start_key, version, limit)
+    }
+
+    fn get_state_values_with_versions_by_key_prefix_from(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        version: Version,
+        limit: usize,
+    ) -> Result<Vec<(StateKey, Version, StateValue)>> {
+        self.inner
+            .get_state_values_with_versions_by_key_prefix_from(
+                key_prefix, start_key, version, limit,
+            )
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        let ledger_info = self.inner.get_latest_ledger_info_option()?;
+        if let Some(ledger_info) = &ledger_info {
+            self.observe(ledger_info);
+        }
+        Ok(ledger_info)
+    }
+
+    fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures> {
+        let ledger_info = self.inner.get_latest_ledger_info()?;
+        self.observe(&ledger_info);
+        Ok(ledger_info)
+    }
+
+    fn get_latest_version_option(&self) -> Result<Option<Version>> {
+        self.inner.get_latest_version_option()
+    }
+
+    fn get_latest_version(&self) -> Result<Version> {
+        let version = self.inner.get_latest_version()?;
+        self.latest_version.fetch_max(version, Ordering::Relaxed);
+        Ok(version)
+    }
+
+    fn get_latest_state_checkpoint_version(&self) -> Result<Option<Version>> {
+        self.inner.get_latest_state_checkpoint_version()
+    }
+
+    fn get_state_snapshot_before(
+        &self,
+        next_version: Version,
+    ) -> Result<Option<(Version, HashValue)>> {
+        self.inner.get_state_snapshot_before(next_version)
+    }
+
+    fn get_latest_commit_metadata(&self) -> Result<(Version, u64)> {
+        self.inner.get_latest_commit_metadata()
+    }
+
+    fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
+        self.inner.get_startup_info()
+    }
+
+    fn get_account_transaction(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<Option<TransactionWithProof>> {
+        self.inner
+            .get_account_transaction(address, seq_num, include_events, ledger_version)
+    }
+
+    fn get_account_transactions(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<AccountTransactionsWithProof> {
+        self.inner
+            .get_account_transactions(address, seq_num, limit, include_events, ledger_version)
+    }
+
+    fn get_state_proof_with_ledger_info(
+        &self,
+        known_version: u64,
+        ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<StateProof> {
+        self.inner
+            .get_state_proof_with_ledger_info(known_version, ledger_info)
+    }
+
+    fn get_state_proof(&self, known_version: u64) -> Result<StateProof> {
+        self.inner.get_state_proof(known_version)
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        self.inner.get_state_value_by_version(state_key, version)
+    }
+
+    fn get_state_value_with_version_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<(Version, StateValue)>> {
+        self.inner
+            .get_state_value_with_version_by_version(state_key, version)
+    }
+
+    fn get_state_proof_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<SparseMerkleProof> {
+        self.inner.get_state_proof_by_version(state_key, version)
+    }
+
+    fn get_state_value_with_proof_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<(Option<StateValue>, SparseMerkleProof)> {
+        self.inner
+            .get_state_value_with_proof_by_version(state_key, version)
+    }
+
+    fn get_latest_executed_trees(&self) -> Result<ExecutedTrees> {
+        self.inner.get_latest_executed_trees()
+    }
+
+    fn get_epoch_ending_ledger_info(&self, known_version: u64) -> Result<LedgerInfoWithSignatures> {
+        self.inner.get_epoch_ending_ledger_info(known_version)
+    }
+
+    fn get_latest_transaction_info_option(&self) -> Result<Option<(Version, TransactionInfo)>> {
+        self.inner.get_latest_transaction_info_option()
+    }
+
+    fn get_accumulator_consistency_proof(
+        &self,
+        client_known_version: Option<Version>,
+        ledger_version: Version,
+    ) -> Result<AccumulatorConsistencyProof> {
+        self.inner
+            .get_accumulator_consistency_proof(client_known_version, ledger_version)
+    }
+
+    fn get_accumulator_summary(
+        &self,
+        ledger_version: Version,
+    ) -> Result<TransactionAccumulatorSummary> {
+        self.inner.get_accumulator_summary(ledger_version)
+    }
+
+    fn get_state_leaf_count(&self, version: Version) -> Result<usize> {
+        self.inner.get_state_leaf_count(version)
+    }
+
+    fn get_state_value_chunk_with_proof(
+        &self,
+        version: Version,
+        start_idx: usize,
+        chunk_size: usize,
+    ) -> Result<StateValueChunkWithProof> {
+        self.inner
+            .get_state_value_chunk_with_proof(version, start_idx, chunk_size)
+    }
+
+    fn get_state_prune_window(&self) -> Result<Option<usize>> {
+        self.inner.get_state_prune_window()
+    }
+
+    fn get_ledger_prune_window(&self) -> Result<Option<usize>> {
+        self.inner.get_ledger_prune_window()
+    }
+
+    fn get_table_info(&self, handle: TableHandle) -> Result<TableInfo> {
+        self.inner.get_table_info(handle)
+    }
+
+    fn indexer_enabled(&self) -> bool {
+        self.inner.indexer_enabled()
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics::{MEMPOOL_CIRCUIT_BREAKER_OPEN, MEMPOOL_CIRCUIT_BREAKER_REJECTIONS};
+use aptos_infallible::Mutex;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Fast-fails mempool submissions while mempool looks unhealthy.
+///
+/// After `failure_threshold` consecutive failures the breaker opens and rejects submissions for
+/// `cooldown`. Once the cooldown elapses a single submission is let through as a probe: if it
+/// succeeds the breaker closes, otherwise it opens again for another cooldown.
+pub struct CircuitBreaker {
+    failure_threshold: u64,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u64,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u64, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Checks whether a submission may go through. While a probe is in flight, the breaker
+    /// stays open for everyone else.
+    pub fn check(&self) -> Result<(), MempoolUnavailable> {
+        let mut state = self.state.lock();
+        let now = Instant::now();
+        match state.open_until {
+            Some(open_until) if now < open_until => {
+                MEMPOOL_CIRCUIT_BREAKER_REJECTIONS.inc();
+                Err(MempoolUnavailable {
+                    retry_after: open_until - now,
+                })
+            }
+            Some(_) => {
+                state.open_until = Some(now + self.cooldown);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.state.lock();
+        state.consecutive_failures = 0;
+        state.open_until = None;
+        MEMPOOL_CIRCUIT_BREAKER_OPEN.set(0);
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock();
+        state.consecutive_failures += 1;
+        if state.open_until.is_some() || state.consecutive_failures >= self.failure_threshold {
+            state.open_until = Some(Instant::now() + self.cooldown);
+            MEMPOOL_CIRCUIT_BREAKER_OPEN.set(1);
+        }
+    }
+}
+
+/// Returned instead of submitting to mempool while the circuit breaker is open
+#[derive(Debug)]
+pub struct MempoolUnavailable {
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for MempoolUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Mempool is unavailable, retry after {}ms",
+            self.retry_after.as_millis()
+        )
+    }
+}
+
+impl std::error::Error for MempoolUnavailable {}
+
+#[cfg(test)]
+mod tests {
+    use super::CircuitBreaker;
+    use std::time::Duration;
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        breaker.record_failure();
+        assert!(breaker.check().is_ok());
+        breaker.record_failure();
+        assert!(breaker.check().is_err());
+    }
+
+    #[test]
+    fn test_success_resets_failures() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn test_probe_after_cooldown() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(0));
+        breaker.record_failure();
+        // The cooldown has elapsed, so one probe goes through
+        assert!(breaker.check().is_ok());
+        breaker.record_success();
+        assert!(breaker.check().is_ok());
+    }
+}
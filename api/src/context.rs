@@ -1,37 +1,124 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{anyhow, ensure, format_err, Context as AnyhowContext, Result};
-use aptos_api_types::{AsConverter, BlockInfo, Error, LedgerInfo, TransactionOnChainData, U64};
+use anyhow::{anyhow, bail, ensure, format_err, Context as AnyhowContext, Result};
+use aptos_api_types::{
+    AsConverter, BlockInfo, Error, LedgerInfo, MoveConverter, MoveModule, MoveType, MoveValue,
+    ScriptFunctionId, TransactionOnChainData, U64,
+};
 use aptos_config::config::{NodeConfig, RoleType};
-use aptos_crypto::HashValue;
-use aptos_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use aptos_crypto::{
+    hash::{CryptoHash, TransactionAccumulatorHasher},
+    HashValue,
+};
+use aptos_infallible::Mutex;
+use aptos_logger::warn;
+use aptos_mempool::{
+    MempoolClientRequest, MempoolClientSender, MempoolQueueStatus, SubmissionStatus,
+};
 use aptos_state_view::StateView;
 use aptos_types::{
-    access_path::Path,
+    access_path::{AccessPath, Path},
     account_address::AccountAddress,
-    account_config::CORE_CODE_ADDRESS,
+    account_config::{AccountResource, CoinStoreResource, CORE_CODE_ADDRESS},
     account_state::AccountState,
     chain_id::ChainId,
-    contract_event::ContractEvent,
-    event::EventKey,
+    contract_event::{ContractEvent, EventWithVersion},
+    epoch_change::EpochChangeProof,
+    event::{EventHandle, EventKey},
     ledger_info::LedgerInfoWithSignatures,
-    state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix, state_value::StateValue},
-    transaction::{SignedTransaction, TransactionWithProof, Version},
-    write_set::WriteOp,
+    mempool_status::MempoolStatusCode,
+    on_chain_config::{new_epoch_event_key, ConfigurationResource, OnChainConfig, ValidatorSet},
+    proof::accumulator::InMemoryAccumulator,
+    state_store::{
+        state_key::StateKey, state_key_prefix::StateKeyPrefix, state_value::StateValue,
+        table::TableHandle,
+    },
+    transaction::{
+        authenticator::AuthenticationKey, ExecutionStatus, RawTransaction, SignedTransaction,
+        Transaction, TransactionInfo, TransactionOutput, TransactionPayload, TransactionWithProof,
+        Version,
+    },
+    write_set::{WriteOp, WriteSet},
+};
+use aptos_vm::{
+    data_cache::{IntoMoveResolver, RemoteStorageOwned},
+    AptosVM, VMExecutor,
+};
+use futures::{
+    channel::oneshot,
+    stream::{self, Stream},
+    SinkExt,
+};
+use lru::LruCache;
+use move_deps::{
+    move_binary_format::file_format::CompiledModule,
+    move_core_types::{
+        ident_str,
+        identifier::{IdentStr, Identifier},
+        language_storage::{ModuleId, ResourceKey, StructTag, TypeTag},
+        move_resource::MoveStructType,
+        vm_status::{DiscardedVMStatus, VMStatus},
+    },
+    move_resource_viewer::AnnotatedMoveValue,
+};
+use rayon::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, VecDeque},
+    convert::Infallible,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use aptos_vm::data_cache::{IntoMoveResolver, RemoteStorageOwned};
-use futures::{channel::oneshot, SinkExt};
-use move_deps::move_core_types::ident_str;
-use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, convert::Infallible, sync::Arc};
 use storage_interface::{
     state_view::{DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView},
     DbReader, Order,
 };
+use tokio::{
+    sync::{Semaphore, SemaphorePermit},
+    time::{sleep, Instant},
+};
+use tokio_util::sync::CancellationToken;
 use warp::{filters::BoxedFilter, Filter, Reply};
 
-use crate::poem_backend::{AptosErrorCode, InternalError};
+use crate::{
+    circuit_breaker::{CircuitBreaker, MempoolUnavailable},
+    log::{RequestContext, SlowQueryLog, SlowQueryTimer},
+    metrics,
+    poem_backend::{
+        build_not_found, AcceptType, AptosErrorCode, BadRequestError, GoneError, InternalError,
+        NotFoundError, PayloadTooLargeError, RequestTimeoutError, ServiceUnavailableError,
+    },
+};
+
+// Bounds the backwards scans done to find a block by its hash or height
+const MAX_BLOCK_SCAN: usize = 1000;
+// Number of versions whose accumulator root hash `verify_version_consistency` re-derives
+const CONSISTENCY_CHECK_WINDOW: u64 = 100;
+
+// Number of epochs whose validator set is cached
+const VALIDATOR_SET_CACHE_SIZE: usize = 16;
+
+// Fixed size of a transaction in a response, and the rough number of bytes per unit of gas it
+// used on top of that, see `estimate_transactions_response_size`
+const RESPONSE_BYTES_PER_TRANSACTION: u64 = 1024;
+const RESPONSE_BYTES_PER_GAS_UNIT: u64 = 16;
+
+// Number of ranges `get_state_values_parallel` cuts an account's keys into, and the number of
+// entries each range reads at once
+const STATE_VALUES_SHARDS: usize = 64;
+const STATE_VALUES_PAGE_SIZE: usize = 256;
+
+// Widest version span `get_events_with_txn_hash` reads the transaction infos of in one go
+const MAX_EVENT_TXN_INFOS_SPAN: u64 = 1000;
+
+/// A converter resolving types as of a version, see `Context::with_converter`
+pub type VersionedConverter<'a> = MoveConverter<'a, RemoteStorageOwned<DbStateView>>;
 
 // Context holds application scope context
 #[derive(Clone)]
@@ -40,6 +127,24 @@ pub struct Context {
     pub db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
     node_config: NodeConfig,
+    mempool_breaker: Arc<CircuitBreaker>,
+    // Committed transactions never change, so cached entries are never invalidated
+    txn_by_hash_cache: Option<Arc<Mutex<LruCache<HashValue, TransactionOnChainData>>>>,
+    // The validator set only changes at epoch boundaries, so it's cached by epoch
+    validator_set_cache: Arc<Mutex<LruCache<u64, ValidatorSet>>>,
+    // Committed blocks never change, so cached entries are never invalidated
+    block_info_cache: Option<Arc<Mutex<BlockInfoCache>>>,
+    // Bounds how many transactions of a page are converted concurrently, the global pool is
+    // used instead when it couldn't be built
+    conversion_pool: Option<Arc<rayon::ThreadPool>>,
+    // Committed timestamps never change, so cached entries are never invalidated
+    block_timestamp_cache: Option<Arc<Mutex<LruCache<u64, u64>>>>,
+    // Bounds how many heavy reads are in flight, see `acquire_heavy_read`
+    heavy_read_permits: Arc<Semaphore>,
+    // The latest ledger info and when it was read, served until it's older than the TTL
+    ledger_info_cache: Option<Arc<Mutex<Option<(Instant, LedgerInfo)>>>>,
+    // Accounts can't be deleted, so once an account exists cached entries are never invalidated
+    existing_accounts_cache: Option<Arc<Mutex<LruCache<AccountAddress, ()>>>>,
 }
 
 impl Context {
@@ -49,14 +154,76 @@ impl Context {
         mp_sender: MempoolClientSender,
         node_config: NodeConfig,
     ) -> Self {
+        let mempool_breaker = Arc::new(CircuitBreaker::new(
+            node_config.api.mempool_circuit_breaker_failure_threshold(),
+            Duration::from_millis(node_config.api.mempool_circuit_breaker_cooldown_ms()),
+        ));
+        let txn_by_hash_cache = match node_config.api.transaction_by_hash_cache_capacity() {
+            0 => None,
+            capacity => Some(Arc::new(Mutex::new(LruCache::new(capacity as usize)))),
+        };
+        let block_info_cache = match node_config.api.block_info_cache_capacity() {
+            0 => None,
+            capacity => Some(Arc::new(Mutex::new(BlockInfoCache::new(capacity as usize)))),
+        };
+        let block_timestamp_cache = match node_config.api.block_timestamp_cache_capacity() {
+            0 => None,
+            capacity => Some(Arc::new(Mutex::new(LruCache::new(capacity as usize)))),
+        };
+        let conversion_pool = match rayon::ThreadPoolBuilder::new()
+            .num_threads(node_config.api.transaction_conversion_threads() as usize)
+            .thread_name(|index| format!("api_conversion_{}", index))
+            .build()
+        {
+            Ok(pool) => Some(Arc::new(pool)),
+            Err(error) => {
+                warn!(
+                    "Failed to build the conversion thread pool, using the global one: {}",
+                    error
+                );
+                None
+            }
+        };
+        let heavy_read_permits = Arc::new(Semaphore::new(
+            node_config.api.max_concurrent_heavy_reads() as usize,
+        ));
+        let existing_accounts_cache = match node_config.api.existing_accounts_cache_capacity() {
+            0 => None,
+            capacity => Some(Arc::new(Mutex::new(LruCache::new(capacity as usize)))),
+        };
+        let ledger_info_cache = match node_config.api.ledger_info_cache_ttl_ms() {
+            0 => None,
+            _ => Some(Arc::new(Mutex::new(None))),
+        };
         Self {
             chain_id,
             db,
             mp_sender,
             node_config,
+            mempool_breaker,
+            txn_by_hash_cache,
+            validator_set_cache: Arc::new(Mutex::new(LruCache::new(VALIDATOR_SET_CACHE_SIZE))),
+            block_info_cache,
+            conversion_pool,
+            block_timestamp_cache,
+            heavy_read_permits,
+            ledger_info_cache,
+            existing_accounts_cache,
         }
     }
 
+    /// Runs `f` with a converter resolving types as of `version`, so that all the conversions of
+    /// a request share one converter rather than building one each
+    pub fn with_converter<T>(
+        &self,
+        version: u64,
+        f: impl FnOnce(&VersionedConverter) -> Result<T>,
+    ) -> Result<T> {
+        let resolver = self.state_view_at_version(version)?.into_move_resolver();
+        let converter = resolver.as_converter(self.db.clone());
+        f(&converter)
+    }
+
     pub fn move_resolver(&self) -> Result<RemoteStorageOwned<DbStateView>> {
         self.db
             .latest_state_checkpoint_view()
@@ -83,15 +250,190 @@ impl Context {
         self.node_config.base.role
     }
 
+    /// Summarizes how far the ledger is behind the wall clock, for monitoring to alert on a
+    /// growing lag rather than just on the health check failing. The lag is 0 if the latest
+    /// block's timestamp is ahead of the local clock.
+    pub fn get_liveness_info(&self) -> Result<LivenessInfo> {
+        let ledger_version = self.resolve_latest_ledger_info()?.version();
+        let block_timestamp_usecs = self.get_block_timestamp(ledger_version)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        Ok(LivenessInfo {
+            ledger_version,
+            block_timestamp_usecs,
+            lag: now.saturating_sub(Duration::from_micros(block_timestamp_usecs)),
+        })
+    }
+
+    /// Describes this node without reading the DB, for clients and load balancers
+    pub fn node_info(&self) -> NodeInfo {
+        let role = self.node_role();
+        NodeInfo {
+            role,
+            is_validator: role.is_validator(),
+            chain_id: self.chain_id(),
+            api_version: env!("CARGO_PKG_VERSION"),
+            git_sha: option_env!("GIT_SHA"),
+        }
+    }
+
     pub fn content_length_limit(&self) -> u64 {
         self.node_config.api.content_length_limit()
     }
 
+    pub fn health_check_mempool_probe(&self) -> bool {
+        self.node_config.api.health_check_mempool_probe()
+    }
+
+    pub fn mempool_status_poll_interval_ms(&self) -> u64 {
+        self.node_config.api.mempool_status_poll_interval_ms()
+    }
+
+    /// Request body size limit for a category of routes, each defaulting to the global limit
+    pub fn content_length_limit_for(&self, route_kind: RouteKind) -> u64 {
+        let api_config = &self.node_config.api;
+        match route_kind {
+            RouteKind::Read => api_config.read_content_length_limit(),
+            RouteKind::SubmitTransaction => api_config.submit_transaction_content_length_limit(),
+            RouteKind::SimulateTransaction => {
+                api_config.simulate_transaction_content_length_limit()
+            }
+        }
+    }
+
+    fn slow_query_timer(&self, log: SlowQueryLog) -> SlowQueryTimer {
+        SlowQueryTimer::new(
+            log,
+            Duration::from_millis(self.node_config.api.slow_query_threshold_ms()),
+        )
+    }
+
     pub fn filter(self) -> impl Filter<Extract = (Context,), Error = Infallible> + Clone {
         warp::any().map(move || self.clone())
     }
 
+    /// Submits a transaction to mempool. Failing or timing out to reach mempool counts against
+    /// the mempool circuit breaker, and while it is open this fails fast with
+    /// `MempoolUnavailable` instead of queueing up more requests.
+    ///
+    /// Transactions whose BCS encoding exceeds the submission content length limit are rejected
+    /// with `TransactionTooLarge`, whichever path they came from.
     pub async fn submit_transaction(&self, txn: SignedTransaction) -> Result<SubmissionStatus> {
+        let size = bcs::serialized_size(&txn)? as u64;
+        let limit = self.content_length_limit_for(RouteKind::SubmitTransaction);
+        if size > limit {
+            return Err(TransactionTooLarge { size, limit }.into());
+        }
+        self.mempool_breaker.check()?;
+        let timeout = Duration::from_millis(self.node_config.api.mempool_submission_timeout_ms());
+        let result = match tokio::time::timeout(timeout, self.send_to_mempool(txn)).await {
+            Ok(result) => result,
+            Err(_) => Err(format_err!(
+                "Timed out after {}ms waiting for mempool",
+                timeout.as_millis()
+            )),
+        };
+        match result {
+            Ok(_) => self.mempool_breaker.record_success(),
+            Err(_) => self.mempool_breaker.record_failure(),
+        }
+        result
+    }
+
+    pub async fn submit_transaction_poem<
+        E: InternalError + PayloadTooLargeError + ServiceUnavailableError,
+    >(
+        &self,
+        txn: SignedTransaction,
+    ) -> Result<SubmissionStatus, E> {
+        self.submit_transaction(txn).await.map_err(|e| {
+            if e.is::<TransactionTooLarge>() {
+                E::payload_too_large(e).error_code(AptosErrorCode::PayloadTooLarge)
+            } else if e.is::<MempoolUnavailable>() {
+                E::service_unavailable(e).error_code(AptosErrorCode::MempoolUnavailable)
+            } else {
+                E::internal(e.context("Failed to submit transaction to mempool"))
+            }
+        })
+    }
+
+    /// Same as `submit_transaction`, for a BCS serialized `SignedTransaction`. Bytes that don't
+    /// deserialize cleanly fail with `InvalidTransactionEncoding` before reaching mempool.
+    pub async fn submit_transaction_bcs(&self, bytes: &[u8]) -> Result<SubmissionStatus> {
+        let txn = bcs::from_bytes::<SignedTransaction>(bytes).map_err(|e| {
+            InvalidTransactionEncoding {
+                error: e.to_string(),
+            }
+        })?;
+        self.submit_transaction(txn).await
+    }
+
+    pub async fn submit_transaction_bcs_poem<
+        E: BadRequestError + InternalError + PayloadTooLargeError + ServiceUnavailableError,
+    >(
+        &self,
+        bytes: &[u8],
+    ) -> Result<SubmissionStatus, E> {
+        let txn = bcs::from_bytes::<SignedTransaction>(bytes).map_err(|e| {
+            E::bad_request(
+                InvalidTransactionEncoding {
+                    error: e.to_string(),
+                }
+                .into(),
+            )
+            .error_code(AptosErrorCode::InvalidTransactionEncoding)
+        })?;
+        self.submit_transaction_poem(txn).await
+    }
+
+    /// Hands a transaction to mempool and returns its hash without waiting for mempool to
+    /// accept it, for submitters that care more about throughput than about feedback.
+    ///
+    /// Only the size and circuit breaker checks of `submit_transaction`, and failing or timing
+    /// out to hand the transaction to mempool, fail the call. A transaction mempool rejects is
+    /// dropped without the caller knowing, so callers should poll `lookup_transaction_by_hash`
+    /// with the returned hash to learn what became of it.
+    pub async fn submit_transaction_async(&self, txn: SignedTransaction) -> Result<HashValue> {
+        let size = bcs::serialized_size(&txn)? as u64;
+        let limit = self.content_length_limit_for(RouteKind::SubmitTransaction);
+        if size > limit {
+            return Err(TransactionTooLarge { size, limit }.into());
+        }
+        self.mempool_breaker.check()?;
+        let hash = txn.clone().committed_hash();
+        let timeout = Duration::from_millis(self.node_config.api.mempool_submission_timeout_ms());
+        let (req_sender, callback) = oneshot::channel();
+        let mut mp_sender = self.mp_sender.clone();
+        let sent = match tokio::time::timeout(
+            timeout,
+            mp_sender.send(MempoolClientRequest::SubmitTransaction(txn, req_sender)),
+        )
+        .await
+        {
+            Ok(sent) => sent.map_err(Into::into),
+            Err(_) => Err(format_err!(
+                "Timed out after {}ms waiting for mempool",
+                timeout.as_millis()
+            )),
+        };
+        if let Err(e) = sent {
+            self.mempool_breaker.record_failure();
+            return Err(e);
+        }
+
+        // Mempool's answer still feeds the circuit breaker, and keeping the callback alive
+        // spares mempool from reporting it as failed. Not answering within the submission
+        // timeout counts as a failure, as it does for `submit_transaction`.
+        let mempool_breaker = self.mempool_breaker.clone();
+        tokio::spawn(async move {
+            match tokio::time::timeout(timeout, callback).await {
+                Ok(Ok(Ok(_))) => mempool_breaker.record_success(),
+                _ => mempool_breaker.record_failure(),
+            }
+        });
+        Ok(hash)
+    }
+
+    async fn send_to_mempool(&self, txn: SignedTransaction) -> Result<SubmissionStatus> {
         let (req_sender, callback) = oneshot::channel();
         self.mp_sender
             .clone()
@@ -101,19 +443,372 @@ impl Context {
         callback.await?
     }
 
+    /// Executes a transaction against the latest state without committing it.
+    ///
+    /// `override_max_gas` replaces the transaction's `max_gas_amount`, so callers can find out
+    /// how much gas a transaction actually needs even when it was built with a low cap. The
+    /// prologue still requires the sender to be able to pay for the overridden amount.
+    pub fn simulate_transaction_with_options(
+        &self,
+        txn: SignedTransaction,
+        options: SimulationOptions,
+    ) -> Result<SimulationOutcome> {
+        if !options.skip_signature_check {
+            ensure!(
+                txn.clone().check_signature().is_err(),
+                "Transaction simulation cannot carry valid signature"
+            );
+        }
+        let txn = match options.override_max_gas {
+            Some(max_gas_amount) => SignedTransaction::new_with_authenticator(
+                RawTransaction::new(
+                    txn.sender(),
+                    txn.sequence_number(),
+                    txn.payload().clone(),
+                    max_gas_amount,
+                    txn.gas_unit_price(),
+                    txn.expiration_timestamp_secs(),
+                    txn.chain_id(),
+                ),
+                txn.authenticator(),
+            ),
+            None => txn,
+        };
+
+        let state_view = &*self.move_resolver()?;
+        let (status, output) = AptosVM::simulate_signed_transaction(&txn, state_view);
+        Ok(SimulationOutcome {
+            status,
+            gas_used: output.gas_used(),
+            output,
+        })
+    }
+
+    /// Re-executes the committed transaction at `version` against the state right before it,
+    /// for debugging discrepancies between the stored output and what the current VM produces.
+    ///
+    /// The replayed data carries the recomputed events and write set, while the transaction
+    /// info and accumulator root hash are the stored ones.
+    pub fn replay_transaction(&self, version: u64) -> Result<ReplayedTransaction> {
+        let _timer =
+            self.slow_query_timer(SlowQueryLog::new("replay_transaction").version(version));
+        ensure!(
+            version > 0,
+            "The genesis transaction has no prior state to replay against"
+        );
+        let ledger_version = self
+            .get_latest_ledger_info_with_signatures()?
+            .ledger_info()
+            .version();
+        let stored = self.get_transaction_by_version(version, ledger_version)?;
+
+        let state_view = self.state_view_at_version(version - 1)?;
+        let output = AptosVM::execute_block(vec![stored.transaction.clone()], &state_view)
+            .map_err(|status| anyhow!("Failed to replay transaction {}: {:?}", version, status))?
+            .pop()
+            .ok_or_else(|| anyhow!("No output replaying transaction {}", version))?;
+        let (write_set, events, _, _) = output.unpack();
+
+        Ok(ReplayedTransaction {
+            write_set_matches: write_set == stored.changes,
+            data: TransactionOnChainData {
+                events,
+                changes: write_set,
+                ..stored
+            },
+        })
+    }
+
+    /// Same as `submit_transaction`, with the mempool and VM statuses mapped into a
+    /// `SubmissionOutcome` for callers that act on the reason a transaction was rejected
+    pub async fn submit_transaction_typed(
+        &self,
+        txn: SignedTransaction,
+    ) -> Result<SubmissionOutcome> {
+        Ok(self.submit_transaction(txn).await?.into())
+    }
+
+    /// Submits a transaction to mempool and waits until it's committed or `timeout` elapses,
+    /// polling storage at the configured `transaction_poll_interval_ms`.
+    ///
+    /// Cancelling `cancel`, e.g. once the client that asked for the transaction disconnected,
+    /// stops the wait right away with `TransactionWaitCancelled`. A transaction mempool already
+    /// accepted stays in mempool, cancelling only stops waiting for it.
+    pub async fn submit_and_wait(
+        &self,
+        txn: SignedTransaction,
+        timeout: Duration,
+        cancel: CancellationToken,
+    ) -> Result<TransactionOnChainData> {
+        let hash = txn.clone().committed_hash();
+        let (mempool_status, vm_status) = tokio::select! {
+            result = self.submit_transaction(txn) => result?,
+            _ = cancel.cancelled() => return Err(TransactionWaitCancelled { hash }.into()),
+        };
+        ensure!(
+            mempool_status.code == MempoolStatusCode::Accepted,
+            "Transaction {} was rejected by mempool: {}, vm status: {:?}",
+            hash,
+            mempool_status,
+            vm_status
+        );
+
+        let poll_interval =
+            Duration::from_millis(self.node_config.api.transaction_poll_interval_ms());
+        let deadline = Instant::now() + timeout;
+        loop {
+            let ledger_version = self.db.get_latest_version()?;
+            if let Some(txn) = self.get_transaction_by_hash(hash, ledger_version)? {
+                return Ok(txn);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            tokio::select! {
+                _ = sleep(poll_interval.min(deadline - now)) => {}
+                _ = cancel.cancelled() => return Err(TransactionWaitCancelled { hash }.into()),
+            }
+        }
+
+        let pending_in_mempool = self.get_pending_transaction_by_hash(hash).await?.is_some();
+        Err(TransactionWaitTimeout {
+            hash,
+            timeout,
+            pending_in_mempool,
+        }
+        .into())
+    }
+
+    /// Polls for new committed versions every `poll_interval` and yields the writes to the
+    /// account's state since the previous poll, starting from the version at the first poll.
+    ///
+    /// Changes are found by diffing the whole account state between the previously seen and
+    /// the latest version, so each poll reads the account twice and holds both copies in
+    /// memory. Versions committed between two polls are folded into one batch, and a value that
+    /// changed and changed back within it doesn't show up. Errors are yielded and polling
+    /// continues, so it's up to the consumer to stop on them.
+    pub fn stream_account_changes(
+        &self,
+        address: AccountAddress,
+        poll_interval: Duration,
+    ) -> impl Stream<Item = Result<Vec<(StateKey, WriteOp)>>> {
+        stream::unfold(
+            (self.clone(), None, true),
+            move |(context, mut last_version, mut first)| async move {
+                loop {
+                    if !first {
+                        sleep(poll_interval).await;
+                    }
+                    first = false;
+
+                    let latest_version = match context.db.get_latest_version() {
+                        Ok(version) => version,
+                        Err(e) => return Some((Err(e), (context, last_version, first))),
+                    };
+                    let from_version = match last_version {
+                        Some(version) if version < latest_version => version,
+                        Some(_) => continue,
+                        None => {
+                            last_version = Some(latest_version);
+                            continue;
+                        }
+                    };
+
+                    match context.diff_account_state(address, from_version, latest_version) {
+                        Ok(diff) => {
+                            last_version = Some(latest_version);
+                            let changes = diff.into_write_ops();
+                            if !changes.is_empty() {
+                                return Some((Ok(changes), (context, last_version, first)));
+                            }
+                        }
+                        Err(e) => return Some((Err(e), (context, last_version, first))),
+                    }
+                }
+            },
+        )
+    }
+
+    /// Yields the info of each block from height `start_height` on, in height order, polling
+    /// for newly committed blocks every `poll_interval` once it has caught up. Blocks are only
+    /// read up to the latest signed ledger info, which only ever covers whole blocks, so no
+    /// block is yielded before all its transactions are committed.
+    ///
+    /// The first block is looked up by height, so like `get_block_infos` it must be one of the
+    /// most recent `MAX_BLOCK_SCAN` blocks, or not committed yet. Every following block starts
+    /// right after the previous one. Errors are yielded and polling continues from the same
+    /// height, so it's up to the consumer to stop on them.
+    pub fn stream_blocks(
+        &self,
+        start_height: u64,
+        poll_interval: Duration,
+    ) -> impl Stream<Item = Result<BlockInfo>> {
+        stream::unfold(
+            (self.clone(), start_height, None, false),
+            move |(context, height, next_start_version, mut wait)| async move {
+                loop {
+                    if wait {
+                        sleep(poll_interval).await;
+                    }
+                    wait = true;
+
+                    let ledger_version = match context.get_latest_ledger_info_with_signatures() {
+                        Ok(ledger_info) => ledger_info.ledger_info().version(),
+                        Err(e) => {
+                            return Some((Err(e), (context, height, next_start_version, wait)))
+                        }
+                    };
+                    let block_info = match next_start_version {
+                        Some(start_version) if start_version > ledger_version => continue,
+                        Some(start_version) => {
+                            context.get_block_info(start_version, ledger_version)
+                        }
+                        None => match context.get_block_info(ledger_version, ledger_version) {
+                            Ok(latest) if latest.block_height < height => continue,
+                            Ok(_) => context.find_block_by_height(height, ledger_version),
+                            Err(e) => Err(e),
+                        },
+                    };
+                    return match block_info {
+                        Ok(block_info) => {
+                            let state = (
+                                context,
+                                block_info.block_height + 1,
+                                Some(block_info.end_version + 1),
+                                false,
+                            );
+                            Some((Ok(block_info), state))
+                        }
+                        Err(e) => Some((Err(e), (context, height, next_start_version, wait))),
+                    };
+                }
+            },
+        )
+    }
+
     pub fn get_latest_ledger_info(&self) -> Result<LedgerInfo, Error> {
-        if let Some(oldest_version) = self.db.get_first_txn_version()? {
-            Ok(LedgerInfo::new(
-                &self.chain_id(),
-                &self.get_latest_ledger_info_with_signatures()?,
-                oldest_version,
-            ))
-        } else {
-            return Err(anyhow! {"Failed to retrieve oldest version"}.into());
+        Ok(self.resolve_latest_ledger_info()?)
+    }
+
+    fn resolve_latest_ledger_info(&self) -> Result<LedgerInfo> {
+        let cache = match &self.ledger_info_cache {
+            Some(cache) => cache,
+            None => return self.read_latest_ledger_info(),
+        };
+        let ttl = Duration::from_millis(self.node_config.api.ledger_info_cache_ttl_ms());
+        let cached = match &*cache.lock() {
+            Some((read_at, ledger_info)) if read_at.elapsed() < ttl => Some(ledger_info.clone()),
+            _ => None,
+        };
+        if let Some(ledger_info) = cached {
+            // The ledger info is epoch dependent, so it can't be served for the rest of the TTL
+            // once a new epoch started. The DB keeps the latest signed ledger info in memory, so
+            // checking its epoch is cheap, unlike resolving the oldest version.
+            let latest_epoch = self
+                .get_latest_ledger_info_with_signatures()?
+                .ledger_info()
+                .epoch();
+            if latest_epoch == ledger_info.epoch {
+                return Ok(ledger_info);
+            }
+        }
+        self.refresh_cached_ledger_info(cache)
+    }
+
+    /// Replaces the cached latest ledger info with a fresh read rather than waiting for the TTL
+    /// to expire, a no-op when the cache is disabled. Safe to call concurrently, a refresh never
+    /// replaces a newer ledger info with an older one.
+    pub fn refresh_ledger_info_cache(&self) -> Result<()> {
+        match &self.ledger_info_cache {
+            Some(cache) => self.refresh_cached_ledger_info(cache).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    fn refresh_cached_ledger_info(
+        &self,
+        cache: &Mutex<Option<(Instant, LedgerInfo)>>,
+    ) -> Result<LedgerInfo> {
+        let ledger_info = self.read_latest_ledger_info()?;
+        let mut cached = cache.lock();
+        match &*cached {
+            // A concurrent refresh already cached a newer ledger info
+            Some((_, current)) if current.version() > ledger_info.version() => Ok(current.clone()),
+            _ => {
+                *cached = Some((Instant::now(), ledger_info.clone()));
+                Ok(ledger_info)
+            }
         }
     }
 
-    // TODO: Add error codes to these errors.
+    // While a reconfiguration is being committed, reading the ledger info can transiently fail
+    // or pair the ledger info of the epoch that just ended with an oldest version that's already
+    // past it. Such a read is retried once, right away, since this runs on the async workers
+    // serving requests and must not sleep; if the retry fails too its error is returned.
+    fn read_latest_ledger_info(&self) -> Result<LedgerInfo> {
+        self.try_read_latest_ledger_info().or_else(|e| {
+            warn!("Retrying to read the latest ledger info: {:#}", e);
+            self.try_read_latest_ledger_info()
+        })
+    }
+
+    fn try_read_latest_ledger_info(&self) -> Result<LedgerInfo> {
+        let ledger_info = self.get_latest_ledger_info_with_signatures()?;
+        let oldest_version = self
+            .db
+            .get_first_txn_version()?
+            .ok_or_else(|| anyhow!("Failed to retrieve oldest version"))?;
+        ensure!(
+            oldest_version <= ledger_info.ledger_info().version(),
+            "Oldest version {} is beyond the latest version {}",
+            oldest_version,
+            ledger_info.ledger_info().version()
+        );
+        Ok(LedgerInfo::new(
+            &self.chain_id(),
+            &ledger_info,
+            oldest_version,
+        ))
+    }
+
+    // The `_latest` variants below resolve the latest ledger info once and read at its version,
+    // returning it alongside the data so callers can report the version the data is as of.
+
+    pub fn get_transactions_latest(
+        &self,
+        start_version: u64,
+        limit: u16,
+    ) -> Result<(LedgerInfo, Vec<TransactionOnChainData>)> {
+        let ledger_info = self.resolve_latest_ledger_info()?;
+        let limit = self.limit_to_ledger(start_version, limit, ledger_info.version());
+        let txns = self.get_transactions(start_version, limit, ledger_info.version())?;
+        Ok((ledger_info, txns))
+    }
+
+    pub fn get_account_transactions_latest(
+        &self,
+        address: AccountAddress,
+        start_seq_number: u64,
+        limit: u16,
+    ) -> Result<(LedgerInfo, Vec<TransactionOnChainData>)> {
+        let ledger_info = self.resolve_latest_ledger_info()?;
+        let txns =
+            self.get_account_transactions(address, start_seq_number, limit, ledger_info.version())?;
+        Ok((ledger_info, txns))
+    }
+
+    pub fn get_events_latest(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        limit: u16,
+    ) -> Result<(LedgerInfo, Vec<ContractEvent>)> {
+        let ledger_info = self.resolve_latest_ledger_info()?;
+        let events = self.get_events(event_key, start, limit, ledger_info.version())?;
+        Ok((ledger_info, events))
+    }
+
     pub fn get_latest_ledger_info_poem<E: InternalError>(&self) -> Result<LedgerInfo, E> {
         if let Some(oldest_version) = self
             .db
@@ -124,13 +819,14 @@ impl Context {
                 &self.chain_id(),
                 &self
                     .get_latest_ledger_info_with_signatures()
-                    .map_err(E::internal)?,
+                    .map_err(|e| E::internal(e).error_code(AptosErrorCode::ReadFromStorageError))?,
                 oldest_version,
             ))
         } else {
-            Err(E::internal(anyhow!(
-                "Failed to retrieve latest ledger info"
-            )))
+            Err(
+                E::internal(anyhow!("Failed to retrieve latest ledger info"))
+                    .error_code(AptosErrorCode::LedgerInfoNotFound),
+            )
         }
     }
 
@@ -138,12 +834,270 @@ impl Context {
         self.db.get_latest_ledger_info()
     }
 
+    /// Returns the oldest and latest versions that can still be read, in that order
+    pub fn get_version_range(&self) -> Result<(u64, u64)> {
+        let oldest_version = self
+            .db
+            .get_first_txn_version()?
+            .ok_or_else(|| format_err!("Failed to retrieve oldest version"))?;
+        let latest_version = self.db.get_latest_version()?;
+        Ok((oldest_version, latest_version))
+    }
+
+    /// Whether `version` can still be read, i.e. it's committed and hasn't been pruned yet
+    pub fn is_version_available(&self, version: u64) -> Result<bool> {
+        let (oldest_version, latest_version) = self.get_version_range()?;
+        Ok(VersionUnavailable::check(version, oldest_version, latest_version).is_ok())
+    }
+
+    /// Returns the epoch and round of the latest committed block. Right after genesis, before
+    /// any block is committed, this is the genesis ledger info's epoch and round 0.
+    pub fn get_epoch_and_round(&self) -> Result<(u64, u64)> {
+        let ledger_info = self.get_latest_ledger_info_with_signatures()?;
+        let ledger_info = ledger_info.ledger_info();
+        Ok((ledger_info.epoch(), ledger_info.round()))
+    }
+
+    /// Returns the ledger info covering a historical `version`: the one ending the epoch the
+    /// version belongs to, or the latest ledger info if that epoch hasn't ended yet
+    pub fn get_ledger_info_at_version(&self, version: u64) -> Result<LedgerInfo> {
+        let (oldest_version, ledger_info) = self.get_signed_ledger_info_covering(version)?;
+        Ok(LedgerInfo::new(
+            &self.chain_id(),
+            &ledger_info,
+            oldest_version,
+        ))
+    }
+
+    /// Same as `get_ledger_info_at_version`, but returns the ledger info with the signatures of
+    /// the validators that committed it, so proofs relative to historical versions can be
+    /// verified. Fails with `VersionUnavailable` if `version` is pruned or beyond the ledger.
+    pub fn get_ledger_info_with_signatures_at_version(
+        &self,
+        version: u64,
+    ) -> Result<LedgerInfoWithSignatures> {
+        Ok(self.get_signed_ledger_info_covering(version)?.1)
+    }
+
+    fn get_signed_ledger_info_covering(
+        &self,
+        version: u64,
+    ) -> Result<(u64, LedgerInfoWithSignatures)> {
+        let oldest_version = self
+            .db
+            .get_first_txn_version()?
+            .ok_or_else(|| format_err!("Failed to retrieve oldest version"))?;
+        let latest_ledger_info = self.get_latest_ledger_info_with_signatures()?;
+        VersionUnavailable::check(
+            version,
+            oldest_version,
+            latest_ledger_info.ledger_info().version(),
+        )?;
+        let ledger_info = self.get_ledger_info_covering(version, latest_ledger_info)?;
+        Ok((oldest_version, ledger_info))
+    }
+
+    // Epoch ending versions increase with the epoch, so binary search for the first epoch that
+    // ends at or after `version`. Falls back to the latest ledger info for the ongoing epoch.
+    fn get_ledger_info_covering(
+        &self,
+        version: u64,
+        latest_ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<LedgerInfoWithSignatures> {
+        let mut covering = latest_ledger_info;
+        let (mut low, mut high) = (0, covering.ledger_info().epoch());
+        while low < high {
+            let epoch = low + (high - low) / 2;
+            let ledger_info = self
+                .db
+                .get_epoch_ending_ledger_infos(epoch, epoch + 1)?
+                .ledger_info_with_sigs
+                .pop()
+                .ok_or_else(|| format_err!("Failed to find ledger info ending epoch {}", epoch))?;
+            if ledger_info.ledger_info().version() >= version {
+                high = epoch;
+                covering = ledger_info;
+            } else {
+                low = epoch + 1;
+            }
+        }
+        Ok(covering)
+    }
+
+    /// Retrieves the epoch ending ledger infos for epochs in `[start_epoch, end_epoch)`, allowing
+    /// clients to verify validator set changes between those epochs
+    pub fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<EpochChangeProof> {
+        ensure!(
+            start_epoch <= end_epoch,
+            "Invalid epoch range: start epoch {} is greater than end epoch {}",
+            start_epoch,
+            end_epoch
+        );
+        // The latest ledger info may carry the next validator set, in which case the epoch it
+        // ends can also be requested
+        let latest_epoch = self
+            .get_latest_ledger_info_with_signatures()?
+            .ledger_info()
+            .next_block_epoch();
+        ensure!(
+            end_epoch <= latest_epoch,
+            "End epoch {} is beyond the latest epoch {}",
+            end_epoch,
+            latest_epoch
+        );
+        self.db
+            .get_epoch_ending_ledger_infos(start_epoch, end_epoch)
+    }
+
     pub fn get_state_value(&self, state_key: &StateKey, version: u64) -> Result<Option<Vec<u8>>> {
         self.db
             .state_view_at_version(Some(version))?
             .get_state_value(state_key)
     }
 
+    /// Returns the BCS bytes of the `struct_tag` resource under `address` at `version`, as
+    /// stored, or `None` if the account doesn't hold one
+    pub fn get_account_resource_bytes(
+        &self,
+        address: AccountAddress,
+        struct_tag: &StructTag,
+        version: u64,
+    ) -> Result<Option<Vec<u8>>> {
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            address,
+            struct_tag.clone(),
+        )));
+        self.get_state_value(&state_key, version)
+    }
+
+    /// Returns the member resources of the `group_tag` resource group under `address` at
+    /// `version`, as BCS bytes keyed by their struct tags. The members of a group share one
+    /// storage slot, the BCS encoded map of them, so they can't be read one by one through
+    /// [`Context::get_account_resource_bytes`]. An account without the group has no members.
+    ///
+    /// Whether `group_tag` is a group is decided from its declaration, see
+    /// `MoveConverter::is_resource_group`, not from whether its bytes happen to decode as a map:
+    /// those of plenty of plain resources do. A group's slot is deleted along with its last
+    /// member, so a stored empty map is the group struct itself, moved to the account as a
+    /// plain resource, and isn't taken for a group either.
+    pub fn get_resource_group(
+        &self,
+        address: AccountAddress,
+        group_tag: &StructTag,
+        version: u64,
+    ) -> Result<BTreeMap<StructTag, Vec<u8>>> {
+        let not_a_group = || {
+            format_err!(
+                "Resource {} of {} at version {} is not a resource group",
+                group_tag,
+                address,
+                version
+            )
+        };
+        if !self.with_converter(version, |converter| converter.is_resource_group(group_tag))? {
+            return Err(not_a_group());
+        }
+        match self.get_account_resource_bytes(address, group_tag, version)? {
+            Some(bytes) => match bcs::from_bytes::<BTreeMap<StructTag, Vec<u8>>>(&bytes) {
+                Ok(members) if !members.is_empty() => Ok(members),
+                _ => Err(not_a_group()),
+            },
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    /// Decodes the resources `struct_tags` of `address` at `version` into the JSON the resources
+    /// routes render, in the order of `struct_tags`, with `None` for those the account doesn't
+    /// have. All of them are read from one state view and decoded with one converter, which is
+    /// cheaper than reading them one by one.
+    pub fn get_resources_decoded(
+        &self,
+        address: AccountAddress,
+        struct_tags: &[StructTag],
+        version: u64,
+    ) -> Result<Vec<Option<serde_json::Value>>> {
+        let keys: Vec<_> = struct_tags
+            .iter()
+            .map(|struct_tag| {
+                StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+                    address,
+                    struct_tag.clone(),
+                )))
+            })
+            .collect();
+        let values = self.get_state_values_batch(&keys, version)?;
+        self.with_converter(version, |converter| {
+            struct_tags
+                .iter()
+                .zip(values)
+                .map(|(struct_tag, bytes)| {
+                    bytes
+                        .map(|bytes| {
+                            let resource = converter.try_into_resource(struct_tag, &bytes)?;
+                            Ok(serde_json::to_value(resource)?)
+                        })
+                        .transpose()
+                })
+                .collect()
+        })
+    }
+
+    /// Returns the address of the account whose authentication key is `auth_key` at `version`.
+    ///
+    /// The framework doesn't keep a table of originating addresses, since
+    /// `0x1::account::rotate_authentication_key` only overwrites the key stored in the account's
+    /// `0x1::account::Account` resource. So the only account this can find is the one at the
+    /// address derived from `auth_key`, as long as its key hasn't been rotated away; an account
+    /// that rotated to `auth_key` from another key isn't found, and `None` is returned.
+    pub fn get_address_for_auth_key(
+        &self,
+        auth_key: AuthenticationKey,
+        version: u64,
+    ) -> Result<Option<AccountAddress>> {
+        let address = auth_key.derived_address();
+        let bytes = match self.get_account_resource_bytes(
+            address,
+            &AccountResource::struct_tag(),
+            version,
+        )? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+        let account: AccountResource = bcs::from_bytes(&bytes)?;
+        Ok((account.authentication_key() == auth_key.as_ref()).then(|| address))
+    }
+
+    /// Returns the version at which the value of `state_key` was last written as of `version`,
+    /// without reading the value itself, or `None` if the key has no value
+    pub fn get_state_value_version(
+        &self,
+        state_key: &StateKey,
+        version: u64,
+    ) -> Result<Option<u64>> {
+        Ok(self
+            .db
+            .get_state_value_with_version_by_version(state_key, version)?
+            .filter(|(_, value)| value.maybe_bytes.is_some())
+            .map(|(last_modified_version, _)| last_modified_version))
+    }
+
+    /// Reads many state keys at one version through a single state view, returning values in
+    /// the order of `keys`
+    pub fn get_state_values_batch(
+        &self,
+        keys: &[StateKey],
+        version: u64,
+    ) -> Result<Vec<Option<Vec<u8>>>> {
+        let state_view = self.db.state_view_at_version(Some(version))?;
+        keys.iter()
+            .map(|state_key| state_view.get_state_value(state_key))
+            .collect()
+    }
+
     pub fn get_state_value_poem<E: InternalError>(
         &self,
         state_key: &StateKey,
@@ -154,54 +1108,704 @@ impl Context {
             .map_err(|e| E::internal(e).error_code(AptosErrorCode::ReadFromStorageError))
     }
 
+    /// Reads `keys` at `version` and discards the values, so that they are in the DB's block
+    /// cache before a burst of requests for them. Reads go out in parallel.
+    ///
+    /// This only helps while the warmed entries fit in the RocksDB block cache alongside the
+    /// live working set, and a node that has been serving for a while has the hot keys cached
+    /// already, so it's mostly useful right after startup.
+    pub fn warm_state_cache(&self, version: u64, keys: &[StateKey]) -> Result<()> {
+        let db = &self.db;
+        keys.par_iter()
+            .try_for_each(|key| db.get_state_value_by_version(key, version).map(|_| ()))
+    }
+
+    /// Reads all of an account's state at `version`. Accounts with more entries than the
+    /// configured `max_account_state_values` fail with `ResponseTooLarge`, their resources have
+    /// to be read one by one instead. The scan stops one entry past the cap, so those accounts
+    /// are never loaded whole.
     pub fn get_state_values(
         &self,
         address: AccountAddress,
         version: u64,
     ) -> Result<HashMap<StateKey, StateValue>> {
-        self.db
-            .get_state_values_by_key_prefix(&StateKeyPrefix::from(address), version)
+        let _permit = self.acquire_heavy_read()?;
+        let limit = self.node_config.api.max_account_state_values();
+        let state_values = self.db.get_state_values_by_key_prefix_from(
+            &StateKeyPrefix::from(address),
+            None,
+            version,
+            (limit as usize).saturating_add(1),
+        )?;
+        self.check_state_values_count(state_values.len())?;
+        Ok(state_values.into_iter().collect())
     }
 
-    pub fn get_account_state(
+    fn check_state_values_count(&self, count: usize) -> Result<()> {
+        let limit = self.node_config.api.max_account_state_values();
+        if count as u64 > limit {
+            return Err(ResponseTooLarge {
+                count: count as u64,
+                limit,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// `get_state_values` bounded by `read_with_timeout`
+    pub async fn get_state_values_with_timeout(
         &self,
         address: AccountAddress,
         version: u64,
-    ) -> Result<Option<AccountState>> {
-        AccountState::from_access_paths_and_values(&self.get_state_values(address, version)?)
-    }
-
-    pub fn get_block_timestamp(&self, version: u64) -> Result<u64> {
-        self.db.get_block_timestamp(version)
+    ) -> Result<HashMap<StateKey, StateValue>> {
+        self.read_with_timeout(move |context| context.get_state_values(address, version))
+            .await
     }
 
-    /// Retrieves information about a block
-    pub fn get_block_info(&self, version: u64, ledger_version: u64) -> Result<BlockInfo> {
-        // We scan the DB to get the block boundaries
-        let (start, end) = match self.db.get_block_boundaries(version, ledger_version) {
-            Ok(inner) => inner,
-            Err(error) => {
-                // None means we can't find the block
-                return Err(anyhow!("Failed to find block boundaries {}", error));
+    /// Same as `get_state_values`, along with metadata about each entry, such as the version it
+    /// was last modified at
+    pub fn get_state_values_with_metadata(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<HashMap<StateKey, (StateValue, StateValueMetadata)>> {
+        let _timer = self.slow_query_timer(
+            SlowQueryLog::new("get_state_values_with_metadata")
+                .address(address)
+                .version(version),
+        );
+        let _permit = self.acquire_heavy_read()?;
+        let limit = self.node_config.api.max_account_state_values();
+        // The scan reads each value under a key holding the version it was written at, so the
+        // versions come with the values
+        let state_values = self.db.get_state_values_with_versions_by_key_prefix_from(
+            &StateKeyPrefix::from(address),
+            None,
+            version,
+            (limit as usize).saturating_add(1),
+        )?;
+        self.check_state_values_count(state_values.len())?;
+        Ok(state_values
+            .into_iter()
+            .map(|(key, last_modified_version, value)| {
+                let metadata = StateValueMetadata {
+                    last_modified_version,
+                };
+                (key, (value, metadata))
+            })
+            .collect())
+    }
+
+    /// Returns every state entry of an account at `version`, sorted by state key, as raw
+    /// `StateValue`s. Both types are BCS serializable, which makes the output suitable for
+    /// offline analysis or migration tooling.
+    pub fn export_account_snapshot(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<Vec<(StateKey, StateValue)>> {
+        let mut entries: Vec<_> = self
+            .get_state_values(address, version)?
+            .into_iter()
+            .collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(entries)
+    }
+
+    /// Same as `get_state_values`, but reads the account's keys as several ranges in parallel and
+    /// merges them, which pays off for accounts holding thousands of resources. The ranges are
+    /// cut at the account's actual keys and read a page at a time, so the
+    /// `max_account_state_values` cap trips as soon as the ranges together read past it. Table
+    /// items aren't stored under the account, `get_table_items` pages through those.
+    pub fn get_state_values_parallel(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<HashMap<StateKey, StateValue>> {
+        let _permit = self.acquire_heavy_read()?;
+        let prefix = StateKeyPrefix::from(address);
+        let cuts = self.cut_account_keys(address, version, STATE_VALUES_SHARDS)?;
+        let read = AtomicU64::new(0);
+        let ranges = cuts
+            .par_iter()
+            .enumerate()
+            .map(|(i, start)| self.read_key_range(&prefix, start, cuts.get(i + 1), version, &read))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut state_values = HashMap::new();
+        for (state_key, state_value) in ranges.into_iter().flatten() {
+            // Ranges are disjoint, so any key showing up twice means the cuts are broken.
+            ensure!(
+                state_values.insert(state_key, state_value).is_none(),
+                "Duplicate state key in parallel scan of account {}",
+                address
+            );
+        }
+        Ok(state_values)
+    }
+
+    // Cuts the keys under `address` into about `shards` disjoint ranges, returning the first key
+    // of each in storage order. Ranges are split by seeking to the midpoint between their first
+    // key and the next cut, so the cuts follow the keys the account actually has.
+    fn cut_account_keys(
+        &self,
+        address: AccountAddress,
+        version: u64,
+        shards: usize,
+    ) -> Result<Vec<StateKey>> {
+        let prefix = StateKeyPrefix::from(address);
+        let seek = |key: Option<&StateKey>| -> Result<Option<StateKey>> {
+            Ok(self
+                .db
+                .get_state_values_by_key_prefix_from(&prefix, key, version, 1)?
+                .into_iter()
+                .next()
+                .map(|(key, _)| key))
+        };
+        let mut cuts = match seek(None)? {
+            Some(first) => vec![first],
+            None => return Ok(vec![]),
+        };
+        while cuts.len() < shards {
+            let mut split = Vec::with_capacity(cuts.len() * 2);
+            for (i, start) in cuts.iter().enumerate() {
+                split.push(start.clone());
+                let end = cuts.get(i + 1);
+                let key = match midpoint_key(start, end) {
+                    Some(probe) => seek(Some(&probe))?,
+                    None => None,
+                };
+                if let Some(key) = key {
+                    let encoded = key.encode()?;
+                    let before_end = match end {
+                        Some(end) => encoded < end.encode()?,
+                        None => true,
+                    };
+                    if encoded > start.encode()? && before_end {
+                        split.push(key);
+                    }
+                }
+            }
+            if split.len() == cuts.len() {
+                break;
+            }
+            cuts = split;
+        }
+        Ok(cuts)
+    }
+
+    // Reads the keys from `start` up to `end`, or to the end of `prefix`, counting them into
+    // `read` so reads over the `max_account_state_values` cap stop early
+    fn read_key_range(
+        &self,
+        prefix: &StateKeyPrefix,
+        start: &StateKey,
+        end: Option<&StateKey>,
+        version: u64,
+        read: &AtomicU64,
+    ) -> Result<Vec<(StateKey, StateValue)>> {
+        let limit = self.node_config.api.max_account_state_values();
+        let end = end.map(StateKey::encode).transpose()?;
+        let mut values = vec![];
+        let mut start = start.clone();
+        loop {
+            let mut page = self.db.get_state_values_by_key_prefix_from(
+                prefix,
+                Some(&start),
+                version,
+                STATE_VALUES_PAGE_SIZE + 1,
+            )?;
+            let next = if page.len() > STATE_VALUES_PAGE_SIZE {
+                page.pop().map(|(key, _)| key)
+            } else {
+                None
+            };
+            for (key, value) in page {
+                if let Some(end) = &end {
+                    if key.encode()? >= *end {
+                        return Ok(values);
+                    }
+                }
+                let count = read.fetch_add(1, Ordering::Relaxed) + 1;
+                if count > limit {
+                    return Err(ResponseTooLarge { count, limit }.into());
+                }
+                values.push((key, value));
+            }
+            match next {
+                Some(next) => start = next,
+                None => return Ok(values),
+            }
+        }
+    }
+
+    /// Compares an account's state at two versions. An account that didn't exist at
+    /// `from_version` shows all of its state as added.
+    pub fn diff_account_state(
+        &self,
+        address: AccountAddress,
+        from_version: u64,
+        to_version: u64,
+    ) -> Result<StateDiff> {
+        ensure!(
+            from_version <= to_version,
+            "from_version {} is after to_version {}",
+            from_version,
+            to_version
+        );
+        let mut before: BTreeMap<_, _> = self
+            .get_state_values(address, from_version)?
+            .into_iter()
+            .collect();
+        let after: BTreeMap<_, _> = self
+            .get_state_values(address, to_version)?
+            .into_iter()
+            .collect();
+
+        let mut diff = StateDiff::default();
+        for (key, value) in after {
+            match before.remove(&key) {
+                None => diff.added.push((key, value)),
+                Some(old_value) if old_value != value => diff.changed.push((key, old_value, value)),
+                Some(_) => {}
+            }
+        }
+        diff.removed = before.into_iter().collect();
+        Ok(diff)
+    }
+
+    /// Returns the raw value of the entry of table `handle` whose BCS serialized key is
+    /// `key_bytes` at `version`, or `None` if the table has no such entry. A key is a serialized
+    /// Move value and so is never empty, an empty one is rejected as malformed.
+    pub fn get_table_item(
+        &self,
+        handle: TableHandle,
+        key_bytes: &[u8],
+        version: u64,
+    ) -> Result<Option<Vec<u8>>> {
+        ensure!(
+            !key_bytes.is_empty(),
+            "Malformed key for table {:?}: keys are BCS serialized Move values and can't be empty",
+            handle
+        );
+        self.get_state_value(&StateKey::table_item(handle, key_bytes.to_vec()), version)
+    }
+
+    /// Pages through the entries of a table in storage order, returning raw key and value bytes.
+    /// `cursor` is the key to resume from, as returned by a previous call, and the returned
+    /// cursor is `None` once there are no more entries. Each page seeks to its cursor and reads
+    /// at most `limit + 1` entries, so tables of any size can be paged through.
+    pub fn get_table_items(
+        &self,
+        handle: TableHandle,
+        version: u64,
+        cursor: Option<StateKey>,
+        limit: u16,
+    ) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, Option<StateKey>)> {
+        let _permit = self.acquire_heavy_read()?;
+        let mut entries = self
+            .db
+            .get_state_values_by_key_prefix_from(
+                &StateKeyPrefix::from(handle),
+                cursor.as_ref(),
+                version,
+                limit as usize + 1,
+            )?
+            .into_iter();
+
+        let mut items = Vec::new();
+        for (key, value) in entries.by_ref().take(limit as usize) {
+            if let (StateKey::TableItem { key, .. }, Some(bytes)) = (key, value.maybe_bytes) {
+                items.push((key, bytes));
+            }
+        }
+        let next_cursor = entries.next().map(|(key, _)| key);
+        Ok((items, next_cursor))
+    }
+
+    pub fn get_account_state(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<Option<AccountState>> {
+        AccountState::from_access_paths_and_values(&self.get_state_values(address, version)?)
+    }
+
+    /// Returns an account's state as of the latest state checkpoint, as long as that's within
+    /// `max_staleness` of the latest version, and as of the latest version otherwise. Reads
+    /// against the checkpoint, which only moves once per block, hit the state caches far more
+    /// often than reads that chase the tip. The version read is returned with the state.
+    pub fn get_account_state_bounded_staleness(
+        &self,
+        address: AccountAddress,
+        max_staleness: Staleness,
+    ) -> Result<(u64, Option<AccountState>)> {
+        let latest_version = self
+            .get_latest_ledger_info_with_signatures()?
+            .ledger_info()
+            .version();
+        let checkpoint_version = self
+            .db
+            .get_latest_state_checkpoint_version()?
+            .map_or(latest_version, |version| version.min(latest_version));
+        let fresh_enough = match max_staleness {
+            Staleness::Versions(versions) => latest_version - checkpoint_version <= versions,
+            Staleness::Duration(duration) => {
+                let behind_usecs = self
+                    .get_block_timestamp(latest_version)?
+                    .saturating_sub(self.get_block_timestamp(checkpoint_version)?);
+                behind_usecs as u128 <= duration.as_micros()
+            }
+        };
+        let version = if fresh_enough {
+            checkpoint_version
+        } else {
+            latest_version
+        };
+        Ok((version, self.get_account_state(address, version)?))
+    }
+
+    /// Whether `address` has no account yet, for faucets to decide whether to create it. This is
+    /// meant to be called at a high rate, so it first reads at the latest state checkpoint, which
+    /// mostly hits the state caches, and only confirms at the latest version that an account
+    /// missing there hasn't been created since. Accounts found to exist are remembered, so retries
+    /// right after an account was created don't read storage again.
+    pub fn needs_account_creation(&self, address: AccountAddress) -> Result<bool> {
+        if let Some(cache) = &self.existing_accounts_cache {
+            if cache.lock().get(&address).is_some() {
+                return Ok(false);
+            }
+        }
+        let latest_version = self
+            .get_latest_ledger_info_with_signatures()?
+            .ledger_info()
+            .version();
+        let checkpoint_version = self
+            .db
+            .get_latest_state_checkpoint_version()?
+            .map_or(latest_version, |version| version.min(latest_version));
+        let struct_tag = AccountResource::struct_tag();
+        let exists = self
+            .get_account_resource_bytes(address, &struct_tag, checkpoint_version)?
+            .is_some()
+            || (checkpoint_version < latest_version
+                && self
+                    .get_account_resource_bytes(address, &struct_tag, latest_version)?
+                    .is_some());
+        if exists {
+            if let Some(cache) = &self.existing_accounts_cache {
+                cache.lock().put(address, ());
+            }
+        }
+        Ok(!exists)
+    }
+
+    /// Returns the number of transactions sent by an account up to `ledger_version`.
+    ///
+    /// Every committed user transaction bumps its sender's sequence number, whether or not it
+    /// executed successfully, so this is the account's sequence number as of `ledger_version`.
+    /// An account that doesn't exist hasn't sent anything, so 0 is returned for it.
+    pub fn get_account_transaction_count(
+        &self,
+        address: AccountAddress,
+        ledger_version: u64,
+    ) -> Result<u64> {
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            address,
+            AccountResource::struct_tag(),
+        )));
+        match self.get_state_value(&state_key, ledger_version)? {
+            Some(bytes) => Ok(bcs::from_bytes::<AccountResource>(&bytes)?.sequence_number()),
+            None => Ok(0),
+        }
+    }
+
+    /// Returns the number of events emitted under `event_key` up to `ledger_version`.
+    ///
+    /// This reads the counter of the event handle stored in the creator's resources instead of
+    /// scanning the events. A handle that can't be found hasn't emitted anything, so 0 is
+    /// returned for it. Finding the handle converts all of the creator's resources, so this
+    /// costs about as much as rendering them.
+    pub fn get_event_count(&self, event_key: &EventKey, ledger_version: u64) -> Result<u64> {
+        Ok(self
+            .get_event_handles(event_key.get_creator_address(), ledger_version)?
+            .into_iter()
+            .find(|handle| handle.key() == event_key)
+            .map_or(0, |handle| handle.count()))
+    }
+
+    /// Returns the number of events emitted by all the event handles in `address`'s resources up
+    /// to `version`, summing their counters rather than reading the events. Like
+    /// `get_event_count`, only handles stored directly in a resource's fields are counted, and
+    /// all of the account's resources are converted to find them.
+    pub fn get_account_total_event_count(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<u64> {
+        Ok(self
+            .get_event_handles(address, version)?
+            .iter()
+            .map(|handle| handle.count())
+            .sum())
+    }
+
+    // Finds the event handles among the fields of an account's resources
+    fn get_event_handles(&self, address: AccountAddress, version: u64) -> Result<Vec<EventHandle>> {
+        let account_state = match self.get_account_state(address, version)? {
+            Some(account_state) => account_state,
+            None => return Ok(vec![]),
+        };
+        self.with_converter(version, |converter| {
+            let mut handles = vec![];
+            for (struct_tag, bytes) in account_state.get_resources() {
+                let resource = converter.view_resource(&struct_tag, bytes)?;
+                let fields = converter.move_struct_fields(&struct_tag, bytes)?;
+                for ((_, annotated), (_, value)) in resource.value.iter().zip(fields) {
+                    // Event handles are generic over the event type, so only their module and
+                    // name are matched
+                    let is_event_handle = matches!(
+                        annotated,
+                        AnnotatedMoveValue::Struct(field)
+                            if field.type_.address == CORE_CODE_ADDRESS
+                                && field.type_.module.as_ident_str() == ident_str!("event")
+                                && field.type_.name.as_ident_str() == ident_str!("EventHandle")
+                    );
+                    if is_event_handle {
+                        handles.push(bcs::from_bytes(&bcs::to_bytes(&value)?)?);
+                    }
+                }
             }
+            Ok(handles)
+        })
+    }
+
+    /// Returns the ABI of a module as of `version`: its public and friend function signatures
+    /// and its struct definitions
+    pub fn get_module_abi(
+        &self,
+        address: AccountAddress,
+        module_name: &str,
+        version: u64,
+    ) -> Result<MoveModule> {
+        self.find_module_abi(address, Identifier::new(module_name)?, version)?
+            .ok_or_else(|| format_err!("Module {}::{} not found", address, module_name))
+    }
+
+    pub fn get_module_abi_poem<E: BadRequestError + NotFoundError + InternalError>(
+        &self,
+        address: AccountAddress,
+        module_name: &str,
+        version: u64,
+    ) -> Result<MoveModule, E> {
+        let name = Identifier::new(module_name).map_err(E::bad_request)?;
+        self.find_module_abi(address, name, version)
+            .context("Failed to parse module ABI")
+            .map_err(|e| E::internal(e).error_code(AptosErrorCode::ReadFromStorageError))?
+            .ok_or_else(|| {
+                build_not_found("Module", format!("{}::{}", address, module_name), version)
+            })
+    }
+
+    /// Decodes the arguments of a script function call (the entry functions of later framework
+    /// versions) using the ABI of the called module as of `version`. Each argument keeps its raw
+    /// BCS bytes, along with its decoded value when its type can be resolved. Scripts, module
+    /// bundles and write sets aren't function calls, so they decode to `NotAnEntryFunction`.
+    pub fn decode_entry_function(
+        &self,
+        txn: &SignedTransaction,
+        version: u64,
+    ) -> Result<DecodedEntryFunction> {
+        let function = match txn.payload() {
+            TransactionPayload::ScriptFunction(function) => function,
+            _ => return Ok(DecodedEntryFunction::NotAnEntryFunction),
+        };
+        let module = function.module();
+        let abi = self.get_module_abi(module.address, module.name().as_str(), version)?;
+        let abi_function = abi
+            .exposed_functions
+            .iter()
+            .find(|abi_function| abi_function.name.0.as_ident_str() == function.function())
+            .ok_or_else(|| format_err!("Function {}::{} not found", module, function.function()))?;
+        // Signers are passed by the VM, not as arguments
+        let params: Vec<_> = abi_function
+            .params
+            .iter()
+            .filter(|param| !param.is_signer())
+            .collect();
+        ensure!(
+            params.len() == function.args().len(),
+            "Function {}::{} takes {} arguments, but was called with {}",
+            module,
+            function.function(),
+            params.len(),
+            function.args().len()
+        );
+
+        let arguments = self.with_converter(version, |converter| {
+            Ok(params
+                .into_iter()
+                .zip(function.args())
+                .map(|(param, bytes)| DecodedArgument {
+                    value: instantiate_type(param, function.ty_args())
+                        .and_then(|type_tag| converter.try_into_move_value(&type_tag, bytes))
+                        .ok(),
+                    bytes: bytes.clone(),
+                })
+                .collect())
+        })?;
+        Ok(DecodedEntryFunction::EntryFunction {
+            function: ScriptFunctionId {
+                module: module.clone().into(),
+                name: function.function().to_owned().into(),
+            },
+            type_arguments: function.ty_args().to_vec(),
+            arguments,
+        })
+    }
+
+    fn find_module_abi(
+        &self,
+        address: AccountAddress,
+        name: Identifier,
+        version: u64,
+    ) -> Result<Option<MoveModule>> {
+        let state_key =
+            StateKey::AccessPath(AccessPath::code_access_path(ModuleId::new(address, name)));
+        self.get_state_value(&state_key, version)?
+            .map(|bytes| {
+                CompiledModule::deserialize(&bytes)
+                    .map(MoveModule::from)
+                    .map_err(|e| anyhow!("Failed to deserialize module: {:?}", e))
+            })
+            .transpose()
+    }
+
+    /// Returns the validator set as of `version`
+    pub fn get_validator_set(&self, version: u64) -> Result<ValidatorSet> {
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            CORE_CODE_ADDRESS,
+            ConfigurationResource::struct_tag(),
+        )));
+        let configuration = self.get_state_value(&state_key, version)?.ok_or_else(|| {
+            format_err!("Configuration resource not found at version {}", version)
+        })?;
+        let epoch = bcs::from_bytes::<ConfigurationResource>(&configuration)?.epoch();
+        if let Some(validator_set) = self.validator_set_cache.lock().get(&epoch) {
+            return Ok(validator_set.clone());
+        }
+
+        let resolver = self.state_view_at_version(version)?.into_move_resolver();
+        let validator_set = ValidatorSet::fetch_config(&resolver)
+            .ok_or_else(|| format_err!("ValidatorSet resource not found at version {}", version))?;
+        self.validator_set_cache
+            .lock()
+            .put(epoch, validator_set.clone());
+        Ok(validator_set)
+    }
+
+    pub fn get_block_timestamp(&self, version: u64) -> Result<u64> {
+        let cache = match &self.block_timestamp_cache {
+            Some(cache) => cache,
+            None => return self.db.get_block_timestamp(version),
         };
+        if let Some(timestamp) = cache.lock().get(&version) {
+            return Ok(*timestamp);
+        }
+        let timestamp = self.db.get_block_timestamp(version)?;
+        // Only cache what's committed, a version beyond the latest ledger info may yet change
+        let ledger_version = self
+            .get_latest_ledger_info_with_signatures()?
+            .ledger_info()
+            .version();
+        if version <= ledger_version {
+            cache.lock().put(version, timestamp);
+        }
+        Ok(timestamp)
+    }
+
+    /// Retrieves information about a block
+    pub fn get_block_info(&self, version: u64, ledger_version: u64) -> Result<BlockInfo> {
+        if let Some(block_info) =
+            self.cached_block_info(|cache| cache.containing(version), ledger_version)
+        {
+            return Ok(block_info);
+        }
+        let block_info = self.read_block_info(version, ledger_version)?;
+        if let Some(cache) = &self.block_info_cache {
+            cache.lock().put(block_info);
+        }
+        Ok(block_info)
+    }
+
+    /// Returns the block containing the transaction at `version`. Genesis is the only
+    /// transaction of block 0. Fails with `VersionUnavailable` for a version beyond
+    /// `ledger_version` or already pruned.
+    pub fn get_block_for_transaction(
+        &self,
+        version: u64,
+        ledger_version: u64,
+    ) -> Result<BlockInfo> {
+        let oldest_version = self.db.get_first_txn_version()?.unwrap_or(0);
+        VersionUnavailable::check(version, oldest_version, ledger_version)?;
+        let block_info = self.get_block_info(version, ledger_version)?;
+        ensure!(
+            block_info.start_version <= version && version <= block_info.end_version,
+            "Block {} [{}, {}] doesn't contain version {}",
+            block_info.block_height,
+            block_info.start_version,
+            block_info.end_version,
+            version
+        );
+        Ok(block_info)
+    }
 
-        let txn_with_proof = self
+    // Only blocks fully committed as of `ledger_version` may be served from the cache, anything
+    // newer must look like it doesn't exist yet
+    fn cached_block_info(
+        &self,
+        lookup: impl FnOnce(&mut BlockInfoCache) -> Option<BlockInfo>,
+        ledger_version: u64,
+    ) -> Option<BlockInfo> {
+        let mut cache = self.block_info_cache.as_ref()?.lock();
+        lookup(&mut cache).filter(|block_info| block_info.end_version <= ledger_version)
+    }
+
+    fn read_block_info(&self, version: u64, ledger_version: u64) -> Result<BlockInfo> {
+        let _timer = self.slow_query_timer(
+            SlowQueryLog::new("get_block_info")
+                .version(version)
+                .ledger_version(ledger_version),
+        );
+        // We scan the DB to get the block boundaries
+        let (start, end) = self
             .db
-            .get_transaction_by_version(start, ledger_version, false)?;
+            .get_block_boundaries(version, ledger_version)
+            .map_err(|error| {
+                self.classify_block_boundaries_error(version, ledger_version, error)
+            })?;
+
+        let (txn, _, _, write_set) = self
+            .get_raw_transactions(start, 1, ledger_version, false)?
+            .pop()
+            .ok_or_else(|| format_err!("transaction not found for version {}", start))?;
 
         // Retrieve block timestamp and hash
         let timestamp;
         let block_hash;
+        let proposer;
         use aptos_types::transaction::Transaction::*;
-        match &txn_with_proof.transaction {
+        match &txn {
             GenesisTransaction(_) => {
                 timestamp = 0;
                 block_hash = HashValue::zero();
+                proposer = None;
             }
             BlockMetadata(inner) => {
                 timestamp = inner.timestamp_usecs();
                 block_hash = inner.id();
+                proposer = Some(inner.proposer().into());
             }
             _ => {
                 return Err(anyhow!(
@@ -219,204 +1823,2226 @@ impl Context {
                 block_hash: block_hash.into(),
                 block_timestamp: timestamp,
                 num_transactions: end.saturating_sub(start).saturating_add(1) as u16,
+                num_user_transactions: 0,
+                proposer,
             });
         }
 
-        // Retrieve block height from the transaction outputs
-        let height_id = ident_str!("height");
-        let block_metadata_type = move_deps::move_core_types::language_storage::StructTag {
+        // Retrieve block height from the block metadata resource written by the block's metadata
+        // transaction. It's read from the transaction's write set rather than from the state at
+        // `start`, which is pruned long before the ledger is.
+        let block_metadata_type = StructTag {
             address: CORE_CODE_ADDRESS,
             module: ident_str!("block").into(),
             name: ident_str!("BlockMetadata").into(),
             type_params: vec![],
         };
 
-        let resolver = self.move_resolver()?;
-        let converter = resolver.as_converter(self.db.clone());
-        let txn = self.get_transaction_by_version(start, ledger_version)?;
-
-        // Parse the resources and find the block metadata resource update
-        let maybe_block_height = txn.changes.iter().find_map(|(key, op)| {
-            if let StateKey::AccessPath(path) = key {
-                if let Path::Resource(typ) = path.get_path() {
-                    // If it's block metadata, we can convert it to get the block height
-                    // And it must be the root address
-                    if path.address == CORE_CODE_ADDRESS && typ == block_metadata_type {
-                        if let WriteOp::Value(value) = op {
-                            if let Ok(mut resource) = converter.try_into_resource(&typ, value) {
-                                if let Some(value) = resource.data.0.remove(&height_id.into()) {
-                                    if let Ok(height) = serde_json::from_value::<U64>(value) {
-                                        return Some(height.0);
-                                    }
-                                }
-                            }
-                        }
-                    }
+        // This should always work unless there's something unexpected in the block format, so
+        // keep the underlying failure around to make that diagnosable
+        let block_metadata = write_set.iter().find_map(|(key, op)| match (key, op) {
+            (StateKey::AccessPath(path), WriteOp::Value(value))
+                if path.address == CORE_CODE_ADDRESS =>
+            {
+                match path.get_path() {
+                    Path::Resource(typ) if typ == block_metadata_type => Some(value),
+                    _ => None,
                 }
             }
-
-            None
+            _ => None,
         });
+        let block_height = match block_metadata
+            .ok_or_else(|| anyhow!("{} not found in the write set", block_metadata_type))
+            .and_then(|bytes| {
+                let resolver = self.move_resolver()?;
+                self.decode_field_from_bytes::<U64>(
+                    &resolver.as_converter(self.db.clone()),
+                    &block_metadata_type,
+                    bytes,
+                    ident_str!("height"),
+                )
+            }) {
+            Ok(height) => height.0,
+            Err(error) => {
+                let error = anyhow!(
+                    "Unable to parse block height in metadata transaction {}:{}: {:#}",
+                    start,
+                    end,
+                    error
+                );
+                warn!("{}", error);
+                if !self.node_config.api.block_height_fallback_enabled() {
+                    return Err(error);
+                }
+                warn!(
+                    "Counting blocks to derive the height of the block starting at {}",
+                    start
+                );
+                self.count_block_height(start, ledger_version)?
+            }
+        };
 
-        // This should always work unless there's something unexpected in the block format
-        if let Some(block_height) = maybe_block_height {
-            Ok(BlockInfo {
-                block_height,
-                start_version: start,
-                end_version: end,
-                block_hash: block_hash.into(),
-                block_timestamp: timestamp,
-                num_transactions: end.saturating_sub(start).saturating_add(1) as u16,
-            })
-        } else {
-            Err(anyhow!(
-                "Unable to find block height in metadata transaction {}:{}",
-                start,
-                end
-            ))
-        }
+        Ok(BlockInfo {
+            block_height,
+            start_version: start,
+            end_version: end,
+            block_hash: block_hash.into(),
+            block_timestamp: timestamp,
+            num_transactions: end.saturating_sub(start).saturating_add(1) as u16,
+            num_user_transactions: self.count_user_transactions(start, end, ledger_version)?,
+            proposer,
+        })
     }
 
-    pub fn get_transactions(
-        &self,
-        start_version: u64,
-        limit: u16,
-        ledger_version: u64,
-    ) -> Result<Vec<TransactionOnChainData>> {
-        let data = self
-            .db
-            .get_transaction_outputs(start_version, limit as u64, ledger_version)?;
-
-        let txn_start_version = data
-            .first_transaction_output_version
-            .ok_or_else(|| format_err!("no start version from database"))?;
+    /// Derives the height of the block starting at `start_version` by counting the blocks before
+    /// it, rather than reading it from the block metadata resource. Counting starts from the
+    /// closest cached block before it, or from genesis, so this can take as many reads as there
+    /// are blocks; it's only meant as a fallback, see `block_height_fallback_enabled`.
+    pub fn count_block_height(&self, start_version: u64, ledger_version: u64) -> Result<u64> {
+        let (mut height, mut version) = self
+            .block_info_cache
+            .as_ref()
+            .and_then(|cache| cache.lock().closest_at_or_before(start_version))
+            .map(|block_info| (block_info.block_height, block_info.start_version))
+            .unwrap_or((0, 0));
+        while version < start_version {
+            let (_, end) = self.db.get_block_boundaries(version, ledger_version)?;
+            version = end + 1;
+            height += 1;
+        }
         ensure!(
-            txn_start_version == start_version,
-            "invalid start version from database: {} != {}",
-            txn_start_version,
+            version == start_version,
+            "No block starts at version {}",
             start_version
         );
-
-        let infos = data.proof.transaction_infos;
-        let transactions_and_outputs = data.transactions_and_outputs;
-
-        ensure!(
-            transactions_and_outputs.len() == infos.len(),
-            "invalid data size from database: {}, {}",
-            transactions_and_outputs.len(),
-            infos.len(),
-        );
-
-        transactions_and_outputs
-            .into_iter()
-            .zip(infos.into_iter())
-            .enumerate()
-            .map(|(i, ((txn, txn_output), info))| {
-                let version = start_version + i as u64;
-                let (write_set, events, _, _) = txn_output.unpack();
-                self.get_accumulator_root_hash(version)
-                    .map(|h| (version, txn, info, events, h, write_set).into())
-            })
-            .collect()
+        Ok(height)
     }
 
-    pub fn get_account_transactions(
-        &self,
-        address: AccountAddress,
-        start_seq_number: u64,
-        limit: u16,
-        ledger_version: u64,
-    ) -> Result<Vec<TransactionOnChainData>> {
-        let txns = self.db.get_account_transactions(
-            address,
-            start_seq_number,
-            limit as u64,
-            true,
+    // Reads the block's transactions without proofs or events, only their kinds are needed
+    fn count_user_transactions(&self, start: u64, end: u64, ledger_version: u64) -> Result<u16> {
+        let txns = self.db.get_transactions_without_proof(
+            start,
+            end - start + 1,
             ledger_version,
+            false,
         )?;
-        txns.into_inner()
-            .into_iter()
-            .map(|t| self.convert_into_transaction_on_chain_data(t))
-            .collect::<Result<Vec<_>>>()
+        Ok(txns
+            .iter()
+            .filter(|(txn, _, _, _)| matches!(txn, Transaction::UserTransaction(_)))
+            .count() as u16)
     }
 
-    pub fn get_transaction_by_hash(
+    /// Reads the resource `struct_tag` of `address` at `version` and decodes its `field`, as it
+    /// would be rendered in JSON, into `T`. Returns `None` if the account has no such resource.
+    pub fn decode_resource_field<T: DeserializeOwned>(
         &self,
-        hash: HashValue,
-        ledger_version: u64,
-    ) -> Result<Option<TransactionOnChainData>> {
-        self.db
-            .get_transaction_by_hash(hash, ledger_version, true)?
-            .map(|t| self.convert_into_transaction_on_chain_data(t))
-            .transpose()
+        version: u64,
+        address: AccountAddress,
+        struct_tag: &StructTag,
+        field: &IdentStr,
+    ) -> Result<Option<T>> {
+        self.with_converter(version, |converter| {
+            self.decode_resource_field_with(converter, version, address, struct_tag, field)
+        })
     }
 
-    pub async fn get_pending_transaction_by_hash(
+    /// Same as `decode_resource_field`, reusing a converter from `with_converter` at `version`
+    /// so that decoding many fields doesn't build a converter each time
+    pub fn decode_resource_field_with<T: DeserializeOwned>(
         &self,
-        hash: HashValue,
-    ) -> Result<Option<SignedTransaction>> {
-        let (req_sender, callback) = oneshot::channel();
-
-        self.mp_sender
-            .clone()
-            .send(MempoolClientRequest::GetTransactionByHash(hash, req_sender))
-            .await
-            .map_err(anyhow::Error::from)?;
+        converter: &VersionedConverter,
+        version: u64,
+        address: AccountAddress,
+        struct_tag: &StructTag,
+        field: &IdentStr,
+    ) -> Result<Option<T>> {
+        match self.get_account_resource_bytes(address, struct_tag, version)? {
+            Some(bytes) => self
+                .decode_field_from_bytes(converter, struct_tag, &bytes, field)
+                .map(Some),
+            None => Ok(None),
+        }
+    }
 
-        callback.await.map_err(anyhow::Error::from)
+    /// Decodes the `field` of a `struct_tag` resource serialized as `bytes`, as it would be
+    /// rendered in JSON, into `T`. For resources that weren't read from the state, such as the
+    /// ones in the write set of a transaction.
+    pub fn decode_field_from_bytes<T: DeserializeOwned>(
+        &self,
+        converter: &VersionedConverter,
+        struct_tag: &StructTag,
+        bytes: &[u8],
+        field: &IdentStr,
+    ) -> Result<T> {
+        let mut resource = converter.try_into_resource(struct_tag, bytes)?;
+        let value = resource
+            .data
+            .0
+            .remove(&field.into())
+            .ok_or_else(|| anyhow!("{} has no {} field", struct_tag, field))?;
+        Ok(serde_json::from_value(value)?)
     }
 
-    pub fn get_transaction_by_version(
+    /// Returns the balance of `coin_type` held by `address` at `version`, reading only the
+    /// `0x1::coin::CoinStore<coin_type>` resource. An account that has no `CoinStore` for the
+    /// coin type (including one that doesn't exist) has a balance of 0.
+    pub fn get_coin_balance(
         &self,
+        address: AccountAddress,
+        coin_type: &StructTag,
         version: u64,
-        ledger_version: u64,
-    ) -> Result<TransactionOnChainData> {
-        self.convert_into_transaction_on_chain_data(self.db.get_transaction_by_version(
+    ) -> Result<u64> {
+        let struct_tag = StructTag {
+            address: CORE_CODE_ADDRESS,
+            module: CoinStoreResource::MODULE_NAME.to_owned(),
+            name: CoinStoreResource::STRUCT_NAME.to_owned(),
+            type_params: vec![TypeTag::Struct(coin_type.clone())],
+        };
+        // `CoinStore` has the same layout whatever the coin type, so it decodes without a
+        // converter
+        match self.get_account_resource_bytes(address, &struct_tag, version)? {
+            Some(bytes) => Ok(bcs::from_bytes::<CoinStoreResource>(&bytes)?.coin()),
+            None => Ok(0),
+        }
+    }
+
+    /// Returns all the resources the framework keeps at `CORE_CODE_ADDRESS` at `version`, keyed
+    /// by their struct tag, as BCS bytes. Among them are the chain configuration resources:
+    /// - `0x1::block::BlockMetadata`, with the latest block height
+    /// - `0x1::chain_id::ChainId`
+    /// - `0x1::reconfiguration::Configuration`, with the current epoch
+    /// - `0x1::timestamp::CurrentTimeMicroseconds`
+    /// - `0x1::stake::ValidatorSet`
+    /// - `0x1::version::Version`
+    /// - `0x1::vm_config::VMConfig`, which holds the gas schedule
+    /// - `0x1::consensus_config::ConsensusConfig`
+    /// - `0x1::transaction_publishing_option::TransactionPublishingOption`
+    pub fn get_system_resources(&self, version: u64) -> Result<HashMap<StructTag, Vec<u8>>> {
+        let account_state = self
+            .get_account_state(CORE_CODE_ADDRESS, version)?
+            .ok_or_else(|| {
+                format_err!(
+                    "No resources at {} at version {}",
+                    CORE_CODE_ADDRESS,
+                    version
+                )
+            })?;
+        Ok(account_state
+            .get_resources()
+            .map(|(struct_tag, bytes)| (struct_tag, bytes.to_vec()))
+            .collect())
+    }
+
+    /// Returns the coin types `address` has a `0x1::coin::CoinStore` for at `version`, whatever
+    /// their balance. Accounts without any coin store yield an empty vec.
+    pub fn get_coin_types(&self, address: AccountAddress, version: u64) -> Result<Vec<StructTag>> {
+        let account_state = match self.get_account_state(address, version)? {
+            Some(account_state) => account_state,
+            None => return Ok(vec![]),
+        };
+        Ok(account_state
+            .get_resources()
+            .filter_map(|(struct_tag, _)| {
+                let is_coin_store = struct_tag.address == CORE_CODE_ADDRESS
+                    && struct_tag.module.as_ident_str() == CoinStoreResource::MODULE_NAME
+                    && struct_tag.name.as_ident_str() == CoinStoreResource::STRUCT_NAME;
+                match struct_tag.type_params.as_slice() {
+                    [TypeTag::Struct(coin_type)] if is_coin_store => Some(coin_type.clone()),
+                    _ => None,
+                }
+            })
+            .collect())
+    }
+
+    // The DB doesn't say why it couldn't find a block, so tell client mistakes (a version beyond
+    // the ledger or already pruned, reported as `VersionUnavailable`) apart from a missing block.
+    fn classify_block_boundaries_error(
+        &self,
+        version: u64,
+        ledger_version: u64,
+        error: anyhow::Error,
+    ) -> anyhow::Error {
+        let oldest_version = self.db.get_first_txn_version().ok().flatten().unwrap_or(0);
+        match VersionUnavailable::check(version, oldest_version, ledger_version) {
+            Err(unavailable) => anyhow::Error::new(unavailable).context(format!(
+                "Failed to find block boundaries for version {} at ledger version {}: {:#}",
+                version, ledger_version, error
+            )),
+            Ok(()) => anyhow!(
+                "Failed to find block boundaries for version {} at ledger version {}, no block \
+                 contains this version: {:#}",
+                version,
+                ledger_version,
+                error
+            ),
+        }
+    }
+
+    /// Retrieves information about the block with the given hash.
+    ///
+    /// Storage has no index from block hash to version, so this walks backwards through the
+    /// blocks from `ledger_version`, reading the boundaries and first transaction of each. The
+    /// walk is bounded to the most recent `MAX_BLOCK_SCAN` blocks, and older blocks are
+    /// reported as not found.
+    pub fn get_block_info_by_hash(
+        &self,
+        block_hash: HashValue,
+        ledger_version: u64,
+    ) -> Result<BlockInfo> {
+        let start = self
+            .find_block_start_by_hash(block_hash, ledger_version)?
+            .ok_or_else(|| {
+                anyhow!(
+                    "Block {} not found in the {} blocks up to version {}",
+                    block_hash,
+                    MAX_BLOCK_SCAN,
+                    ledger_version
+                )
+            })?;
+        self.get_block_info(start, ledger_version)
+    }
+
+    pub fn get_block_info_by_hash_poem<E: NotFoundError + InternalError>(
+        &self,
+        block_hash: HashValue,
+        ledger_version: u64,
+    ) -> Result<BlockInfo, E> {
+        let start = self
+            .find_block_start_by_hash(block_hash, ledger_version)
+            .context("Failed to scan blocks")
+            .map_err(|e| E::internal(e).error_code(AptosErrorCode::ReadFromStorageError))?
+            .ok_or_else(|| build_not_found("Block", block_hash, ledger_version))?;
+        self.get_block_info(start, ledger_version)
+            .context("Failed to retrieve block info")
+            .map_err(|e| E::internal(e).error_code(AptosErrorCode::ReadFromStorageError))
+    }
+
+    fn find_block_start_by_hash(
+        &self,
+        block_hash: HashValue,
+        ledger_version: u64,
+    ) -> Result<Option<u64>> {
+        let _timer = self.slow_query_timer(
+            SlowQueryLog::new("find_block_start_by_hash").ledger_version(ledger_version),
+        );
+        let mut version = ledger_version;
+        for _ in 0..MAX_BLOCK_SCAN {
+            let (start, _) = self.db.get_block_boundaries(version, ledger_version)?;
+            let txn = self
+                .db
+                .get_transaction_by_version(start, ledger_version, false)?
+                .transaction;
+            let id = match txn {
+                Transaction::BlockMetadata(metadata) => metadata.id(),
+                // Genesis doesn't have a block metadata, and is reported with a zero hash
+                Transaction::GenesisTransaction(_) => HashValue::zero(),
+                _ => bail!("Block starting at {} has no BlockMetadata", start),
+            };
+            if id == block_hash {
+                return Ok(Some(start));
+            }
+            if start == 0 {
+                break;
+            }
+            version = start - 1;
+        }
+        Ok(None)
+    }
+
+    /// Returns the total gas used by the transactions of the block at `height`.
+    ///
+    /// Like `get_block_info_by_hash`, this has to walk backwards from the latest block, so only
+    /// the most recent `MAX_BLOCK_SCAN` blocks can be looked up.
+    pub fn get_block_gas_used(&self, height: u64, ledger_version: u64) -> Result<u64> {
+        let _timer = self.slow_query_timer(
+            SlowQueryLog::new("get_block_gas_used").ledger_version(ledger_version),
+        );
+        // Genesis is block 0, and isn't charged any gas
+        if height == 0 {
+            return Ok(0);
+        }
+        let block = self.find_block_by_height(height, ledger_version)?;
+        Ok(self
+            .get_raw_transactions(
+                block.start_version,
+                block.num_transactions,
+                ledger_version,
+                false,
+            )?
+            .iter()
+            .map(|(_, info, _, _)| info.gas_used())
+            .sum())
+    }
+
+    fn find_block_by_height(&self, height: u64, ledger_version: u64) -> Result<BlockInfo> {
+        if let Some(block_info) = self.cached_block_info(|cache| cache.get(height), ledger_version)
+        {
+            return Ok(block_info);
+        }
+        let latest = self.get_block_info(ledger_version, ledger_version)?;
+        ensure!(
+            height <= latest.block_height,
+            "Block height {} is beyond the latest block height {} at version {}",
+            height,
+            latest.block_height,
+            ledger_version
+        );
+        let depth = latest.block_height - height;
+        ensure!(
+            depth <= MAX_BLOCK_SCAN as u64,
+            "Block height {} is more than {} blocks older than the latest block height {}",
+            height,
+            MAX_BLOCK_SCAN,
+            latest.block_height
+        );
+        let mut start = latest.start_version;
+        for _ in 0..depth {
+            let (previous_start, _) = self.db.get_block_boundaries(start - 1, ledger_version)?;
+            start = previous_start;
+        }
+        self.get_block_info(start, ledger_version)
+    }
+
+    /// Returns the info of up to `limit` consecutive blocks starting at height `start_height`,
+    /// in height order. Blocks are only reachable by walking back from the latest block, so as
+    /// with `get_block_gas_used` only the most recent `MAX_BLOCK_SCAN` blocks can be returned.
+    ///
+    /// If any block fails to resolve, the error of the lowest such height is returned.
+    pub fn get_block_infos(
+        &self,
+        start_height: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<BlockInfo>> {
+        let _timer = self.slow_query_timer(
+            SlowQueryLog::new("get_block_infos")
+                .limit(limit)
+                .ledger_version(ledger_version),
+        );
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+        let latest = self.get_block_info(ledger_version, ledger_version)?;
+        ensure!(
+            start_height <= latest.block_height,
+            "Block height {} is beyond the latest block height {} at version {}",
+            start_height,
+            latest.block_height,
+            ledger_version
+        );
+        ensure!(
+            latest.block_height - start_height <= MAX_BLOCK_SCAN as u64,
+            "Block height {} is more than {} blocks older than the latest block height {}",
+            start_height,
+            MAX_BLOCK_SCAN,
+            latest.block_height
+        );
+        let end_height = latest
+            .block_height
+            .min(start_height.saturating_add(limit as u64 - 1));
+
+        // Finding where each block starts is a cheap walk over block boundaries, it's resolving
+        // the info of each block that's worth doing in parallel
+        let mut start_versions = vec![];
+        let mut start = latest.start_version;
+        for height in (start_height..=latest.block_height).rev() {
+            if height <= end_height {
+                start_versions.push((height, start));
+            }
+            if height > start_height {
+                start = self.db.get_block_boundaries(start - 1, ledger_version)?.0;
+            }
+        }
+        start_versions.reverse();
+
+        let results: Vec<_> = start_versions
+            .into_par_iter()
+            .map(|(height, start)| (height, self.get_block_info(start, ledger_version)))
+            .collect();
+        results
+            .into_iter()
+            .map(|(height, result)| {
+                result.with_context(|| format!("Failed to resolve block at height {}", height))
+            })
+            .collect()
+    }
+
+    // Lowers `limit` to the configured maximum of `method`, counting how often that happens
+    fn clamp_limit(&self, method: &'static str, limit: u16, max: u16) -> u16 {
+        if limit > max {
+            metrics::LIMIT_CLAMPED.with_label_values(&[method]).inc();
+            max
+        } else {
+            limit
+        }
+    }
+
+    /// Takes one of the `max_concurrent_heavy_reads` permits for a heavy read, held until the
+    /// returned guard is dropped. Rather than queuing when every permit is in use, this fails
+    /// with `Overloaded` so that load spikes don't exhaust the threads serving requests.
+    pub fn acquire_heavy_read(&self) -> Result<HeavyReadPermit<'_>> {
+        match self.heavy_read_permits.try_acquire() {
+            Ok(permit) => {
+                metrics::HEAVY_READS_IN_FLIGHT.inc();
+                Ok(HeavyReadPermit { _permit: permit })
+            }
+            Err(_) => {
+                metrics::HEAVY_READS_REJECTED.inc();
+                Err(Overloaded {
+                    limit: self.node_config.api.max_concurrent_heavy_reads(),
+                }
+                .into())
+            }
+        }
+    }
+
+    /// Runs a heavy read on the blocking thread pool, and gives up on it with `ReadTimeout` once
+    /// it has run for longer than the configured `max_read_duration_ms`. A read that timed out
+    /// still runs to completion in the background, but its result is dropped.
+    pub async fn read_with_timeout<T, F>(&self, read: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Context) -> Result<T> + Send + 'static,
+    {
+        let timeout = Duration::from_millis(self.node_config.api.max_read_duration_ms());
+        let context = self.clone();
+        let request = RequestContext::current();
+        let read = tokio::task::spawn_blocking(move || {
+            let _request = request.enter();
+            read(&context)
+        });
+        match tokio::time::timeout(timeout, read).await {
+            Ok(result) => result?,
+            Err(_) => Err(ReadTimeout { timeout }.into()),
+        }
+    }
+
+    pub async fn read_with_timeout_poem<T, F, E>(&self, read: F) -> Result<T, E>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Context) -> Result<T> + Send + 'static,
+        E: InternalError + RequestTimeoutError + ServiceUnavailableError,
+    {
+        self.read_with_timeout(read).await.map_err(|e| {
+            if e.is::<ReadTimeout>() {
+                E::request_timeout(e).error_code(AptosErrorCode::Timeout)
+            } else if e.is::<Overloaded>() {
+                E::service_unavailable(e).error_code(AptosErrorCode::Overloaded)
+            } else {
+                E::internal(e).error_code(AptosErrorCode::ReadFromStorageError)
+            }
+        })
+    }
+
+    /// `get_transactions` bounded by `read_with_timeout`
+    pub async fn get_transactions_with_timeout(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        self.read_with_timeout(move |context| {
+            context.get_transactions(start_version, limit, ledger_version)
+        })
+        .await
+    }
+
+    pub fn get_transactions(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        self.get_transactions_opts(start_version, limit, ledger_version, true)
+    }
+
+    /// Bounds `limit` to the versions left in the ledger from `start_version`, so the last page
+    /// of transactions can be asked for without knowing how many are left
+    pub fn limit_to_ledger(&self, start_version: u64, limit: u16, ledger_version: u64) -> u16 {
+        match (ledger_version - start_version.min(ledger_version)).checked_add(1) {
+            Some(remaining) if remaining < limit as u64 => remaining as u16,
+            _ => limit,
+        }
+    }
+
+    /// [`Context::get_transactions`] leaving the events of the transactions out when
+    /// `include_events` is false, for lists that don't render them. The events aren't read from
+    /// storage then, and the returned transactions carry none, so converting and serializing a
+    /// page costs nothing for them either.
+    pub fn get_transactions_opts(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+        include_events: bool,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        let limit = self.clamp_limit(
+            "get_transactions",
+            limit,
+            self.node_config.api.max_transactions_page_size(),
+        );
+        let _permit = self.acquire_heavy_read()?;
+        let _timer = self.slow_query_timer(
+            SlowQueryLog::new("get_transactions")
+                .version(start_version)
+                .limit(limit)
+                .ledger_version(ledger_version),
+        );
+        // A page has to lie within the ledger, a page reaching past the ledger version is
+        // rejected rather than cut short. Callers reading the last page bound their limit with
+        // `limit_to_ledger` first.
+        ensure!(
+            start_version <= ledger_version,
+            "Start version {} is beyond the ledger version {}",
+            start_version,
+            ledger_version
+        );
+        let end_version = start_version.checked_add(limit as u64).ok_or_else(|| {
+            format_err!(
+                "A page of {} transactions from version {} overflows the version range",
+                limit,
+                start_version
+            )
+        })?;
+        ensure!(
+            end_version <= ledger_version.saturating_add(1),
+            "A page of {} transactions from version {} ends past the ledger version {}",
+            limit,
+            start_version,
+            ledger_version
+        );
+        self.get_raw_transactions(start_version, limit, ledger_version, include_events)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, (txn, info, events, write_set))| {
+                let version = start_version
+                    .checked_add(i as u64)
+                    .ok_or_else(|| format_err!("Version {} + {} overflows", start_version, i))?;
+                self.get_accumulator_root_hash(version)
+                    .map(|h| (version, txn, info, events, h, write_set).into())
+            })
+            .collect()
+    }
+
+    pub async fn get_transactions_poem<E>(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<TransactionOnChainData>, E>
+    where
+        E: InternalError + RequestTimeoutError + ServiceUnavailableError,
+    {
+        self.read_with_timeout_poem(move |context| {
+            context
+                .get_transactions(start_version, limit, ledger_version)
+                .context("Failed to read raw transactions from storage")
+        })
+        .await
+    }
+
+    /// Estimates the size of the page `get_transactions` would return, so a response that would
+    /// be too large can be rejected before it's built. Only the transaction infos are read: gas
+    /// is charged for the bytes a transaction carries, writes and emits, so the estimate grows
+    /// with the gas used, on top of a fixed size per transaction. It's a heuristic rather than a
+    /// bound, transactions using no gas, like genesis, are only counted at the fixed size.
+    pub fn estimate_transactions_response_size(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<u64> {
+        Ok(self
+            .db
+            .get_transaction_infos(start_version, limit as u64, ledger_version)?
+            .iter()
+            .map(|info| {
+                RESPONSE_BYTES_PER_TRANSACTION
+                    .saturating_add(info.gas_used().saturating_mul(RESPONSE_BYTES_PER_GAS_UNIT))
+            })
+            .fold(0, u64::saturating_add))
+    }
+
+    /// Fails with `ResponseSizeExceeded` if the estimated size of the page `get_transactions`
+    /// would return is over the configured `max_transactions_response_bytes`, which is disabled
+    /// when 0
+    pub fn check_transactions_response_size(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<()> {
+        let limit_bytes = self.node_config.api.max_transactions_response_bytes();
+        if limit_bytes == 0 {
+            return Ok(());
+        }
+        let estimated_size =
+            self.estimate_transactions_response_size(start_version, limit, ledger_version)?;
+        if estimated_size > limit_bytes {
+            return Err(ResponseSizeExceeded {
+                estimated_size,
+                limit: limit_bytes,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Same as `get_transactions`, but leaves out transactions whose execution failed.
+    ///
+    /// Transactions are filtered after being read, so `limit` bounds the number of versions
+    /// scanned starting at `start_version`, not the number returned. Callers paging through the
+    /// ledger should advance `start_version` by `limit` regardless of how many come back.
+    pub fn get_successful_transactions(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        Ok(self
+            .get_transactions(start_version, limit, ledger_version)?
+            .into_iter()
+            .filter(|txn| txn.info.status().is_success())
+            .collect())
+    }
+
+    /// Same as `get_transactions`, but for the inclusive range `start_version..=end_version`,
+    /// which may span at most a page of transactions
+    pub fn get_transactions_range(
+        &self,
+        start_version: u64,
+        end_version: u64,
+        ledger_version: u64,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        ensure!(
+            start_version <= end_version,
+            "start_version {} is after end_version {}",
+            start_version,
+            end_version
+        );
+        ensure!(
+            end_version <= ledger_version,
+            "end_version {} is beyond the ledger version {}",
+            end_version,
+            ledger_version
+        );
+        let limit = end_version - start_version + 1;
+        let max_limit = self.node_config.api.max_transactions_page_size();
+        ensure!(
+            limit <= max_limit as u64,
+            "Range of {} transactions exceeds the limit {}",
+            limit,
+            max_limit
+        );
+        self.get_transactions(start_version, limit as u16, ledger_version)
+    }
+
+    /// Same as `get_transactions`, but returns the transactions as stored, for clients that
+    /// consume BCS and don't need the JSON oriented `TransactionOnChainData`.
+    pub fn get_transactions_bcs(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<(Transaction, TransactionInfo, Vec<ContractEvent>, WriteSet)>> {
+        let limit = self.clamp_limit(
+            "get_transactions_bcs",
+            limit,
+            self.node_config.api.max_transactions_page_size(),
+        );
+        let _timer = self.slow_query_timer(
+            SlowQueryLog::new("get_transactions_bcs")
+                .version(start_version)
+                .limit(limit)
+                .ledger_version(ledger_version),
+        );
+        self.get_raw_transactions(start_version, limit, ledger_version, true)
+    }
+
+    /// Returns the events emitted by each transaction in `limit` versions from `start_version`,
+    /// for consumers that only index events and don't need the transactions or their writes
+    pub fn get_events_in_range(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<(u64, Vec<ContractEvent>)>> {
+        let limit = self.clamp_limit(
+            "get_events_in_range",
+            limit,
+            self.node_config.api.max_transactions_page_size(),
+        );
+        let _permit = self.acquire_heavy_read()?;
+        let _timer = self.slow_query_timer(
+            SlowQueryLog::new("get_events_in_range")
+                .version(start_version)
+                .limit(limit)
+                .ledger_version(ledger_version),
+        );
+        Ok(self
+            .get_raw_transactions(start_version, limit, ledger_version, true)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, (_, _, events, _))| (start_version + i as u64, events))
+            .collect())
+    }
+
+    /// Whether the transaction at `version` ends an epoch. A transaction that reconfigures the
+    /// chain emits a `0x1::reconfiguration::NewEpochEvent` on the new epoch event handle
+    /// (`new_epoch_event_key`), which is what the VM and executor check for as well.
+    pub fn is_epoch_boundary(&self, version: u64) -> Result<bool> {
+        let ledger_version = self
+            .get_latest_ledger_info_with_signatures()?
+            .ledger_info()
+            .version();
+        let (_, _, events, _) = self
+            .get_raw_transactions(version, 1, ledger_version, true)?
+            .pop()
+            .ok_or_else(|| format_err!("transaction not found for version {}", version))?;
+        let new_epoch_event_key = new_epoch_event_key();
+        Ok(events
+            .iter()
+            .any(|event| *event.key() == new_epoch_event_key))
+    }
+
+    /// Returns how the transaction at `version` went, without converting it or its events and
+    /// write set. Genesis, block metadata and state checkpoint transactions are run by the
+    /// system rather than a user, they succeed and are charged no gas.
+    pub fn get_transaction_status(
+        &self,
+        version: u64,
+        ledger_version: u64,
+    ) -> Result<TransactionStatusSummary> {
+        let (txn, info, _, _) = self
+            .get_raw_transactions(version, 1, ledger_version, false)?
+            .pop()
+            .ok_or_else(|| format_err!("transaction not found for version {}", version))?;
+        Ok(TransactionStatusSummary {
             version,
+            is_user_transaction: matches!(txn, Transaction::UserTransaction(_)),
+            success: info.status().is_success(),
+            status: info.status().clone(),
+            gas_used: info.gas_used(),
+        })
+    }
+
+    fn get_raw_transactions(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+        fetch_events: bool,
+    ) -> Result<Vec<(Transaction, TransactionInfo, Vec<ContractEvent>, WriteSet)>> {
+        self.db.get_transactions_without_proof(
+            start_version,
+            limit as u64,
             ledger_version,
+            fetch_events,
+        )
+    }
+
+    /// Returns the write ops touching state keys under `address` of the `limit` transactions
+    /// from `start_version`, by version. Versions that didn't write any of them are left out.
+    pub fn get_writes_for_address(
+        &self,
+        address: AccountAddress,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<(u64, Vec<WriteOp>)>> {
+        let limit = self.clamp_limit(
+            "get_writes_for_address",
+            limit,
+            self.node_config.api.max_transactions_page_size(),
+        );
+        let prefix = StateKeyPrefix::from(address);
+        let mut writes = vec![];
+        for (version, (_, _, _, write_set)) in (start_version..).zip(self.get_raw_transactions(
+            start_version,
+            limit,
+            ledger_version,
+            false,
+        )?) {
+            let mut ops = vec![];
+            for (state_key, write_op) in write_set.iter() {
+                if prefix.is_prefix(state_key)? {
+                    ops.push(write_op.clone());
+                }
+            }
+            if !ops.is_empty() {
+                writes.push((version, ops));
+            }
+        }
+        Ok(writes)
+    }
+
+    pub fn get_account_transactions(
+        &self,
+        address: AccountAddress,
+        start_seq_number: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        let limit = self.clamp_limit(
+            "get_account_transactions",
+            limit,
+            self.node_config.api.max_account_transactions_page_size(),
+        );
+        let _permit = self.acquire_heavy_read()?;
+        let _timer = self.slow_query_timer(
+            SlowQueryLog::new("get_account_transactions")
+                .address(address)
+                .start(start_seq_number)
+                .limit(limit)
+                .ledger_version(ledger_version),
+        );
+        let txns = self.db.get_account_transactions(
+            address,
+            start_seq_number,
+            limit as u64,
             true,
-        )?)
+            ledger_version,
+        )?;
+        // Each conversion reads the transaction's output and accumulator root hash, so they're
+        // done concurrently, in the order of the page
+        let convert = || {
+            txns.into_inner()
+                .into_par_iter()
+                .map(|t| self.convert_into_transaction_on_chain_data(t))
+                .collect::<Result<Vec<_>>>()
+        };
+        match &self.conversion_pool {
+            Some(pool) => pool.install(convert),
+            None => convert(),
+        }
+    }
+
+    /// Same as `get_account_transactions`, but only returns the transactions committed in the
+    /// inclusive version range `start_version..=end_version`.
+    ///
+    /// `limit` bounds the transactions read starting at `start_seq_number`, before they're
+    /// filtered by version, so fewer than `limit` transactions may be returned even though the
+    /// account has more in the range. Transactions past `end_version` are never read since it
+    /// is used as the ledger version, but ones before `start_version` count against `limit`,
+    /// so callers should pick `start_seq_number` close to the start of the range.
+    pub fn get_account_transactions_in_range(
+        &self,
+        address: AccountAddress,
+        start_seq_number: u64,
+        limit: u16,
+        start_version: u64,
+        end_version: u64,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        ensure!(
+            start_version <= end_version,
+            "Start version {} is after end version {}",
+            start_version,
+            end_version
+        );
+        let mut txns =
+            self.get_account_transactions(address, start_seq_number, limit, end_version)?;
+        txns.retain(|txn| txn.version >= start_version);
+        Ok(txns)
+    }
+
+    pub fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: u64,
+    ) -> Result<Option<TransactionOnChainData>> {
+        // A transaction committed after `ledger_version` must look like it doesn't exist yet, so
+        // only serve cached entries at or below it. Otherwise the DB has the final say.
+        if let Some(cache) = &self.txn_by_hash_cache {
+            if let Some(txn) = cache.lock().get(&hash) {
+                if txn.version <= ledger_version {
+                    return Ok(Some(txn.clone()));
+                }
+            }
+        }
+
+        let txn = self
+            .db
+            .get_transaction_by_hash(hash, ledger_version, true)?
+            .map(|t| self.convert_into_transaction_on_chain_data(t))
+            .transpose()?;
+        if let (Some(cache), Some(txn)) = (&self.txn_by_hash_cache, &txn) {
+            cache.lock().put(hash, txn.clone());
+        }
+        Ok(txn)
+    }
+
+    /// Same as `get_transaction_by_hash`, but returns the transaction as stored along with its
+    /// proof, which is relative to `ledger_version`, so light clients can verify it against a
+    /// ledger info they trust
+    pub fn get_transaction_by_hash_with_proof(
+        &self,
+        hash: HashValue,
+        ledger_version: u64,
+    ) -> Result<Option<TransactionWithProof>> {
+        self.db.get_transaction_by_hash(hash, ledger_version, true)
+    }
+
+    /// Looks up several transactions by hash, returning them in the order of `hashes`, with
+    /// `None` for the ones not committed as of `ledger_version`. The distinct hashes missing from
+    /// the cache are resolved to versions in one batch, then those versions are read in another,
+    /// without proofs, and only the accumulator root hash of each is looked up on its own.
+    pub fn get_transactions_by_hashes(
+        &self,
+        hashes: &[HashValue],
+        ledger_version: u64,
+    ) -> Result<Vec<Option<TransactionOnChainData>>> {
+        let _timer = self.slow_query_timer(
+            SlowQueryLog::new("get_transactions_by_hashes").ledger_version(ledger_version),
+        );
+        let unique: BTreeSet<HashValue> = hashes.iter().copied().collect();
+        let mut found: HashMap<HashValue, Option<TransactionOnChainData>> = HashMap::new();
+        if let Some(cache) = &self.txn_by_hash_cache {
+            let mut cache = cache.lock();
+            for hash in &unique {
+                if let Some(txn) = cache.get(hash) {
+                    if txn.version <= ledger_version {
+                        found.insert(*hash, Some(txn.clone()));
+                    }
+                }
+            }
+        }
+
+        let missing: Vec<HashValue> = unique
+            .into_iter()
+            .filter(|hash| !found.contains_key(hash))
+            .collect();
+        let versions = self
+            .db
+            .get_transaction_versions_by_hashes(&missing, ledger_version)?;
+        // Distinct hashes were committed at distinct versions
+        let committed: Vec<(HashValue, u64)> = missing
+            .iter()
+            .zip(&versions)
+            .filter_map(|(hash, version)| version.map(|version| (*hash, version)))
+            .collect();
+        let committed_versions: Vec<u64> = committed.iter().map(|(_, version)| *version).collect();
+        let txns =
+            self.db
+                .get_transactions_by_versions(&committed_versions, ledger_version, true)?;
+        let root_hashes = committed_versions
+            .par_iter()
+            .map(|version| self.get_accumulator_root_hash(*version))
+            .collect::<Result<Vec<_>>>()?;
+
+        for (hash, version) in missing.iter().zip(versions) {
+            if version.is_none() {
+                found.insert(*hash, None);
+            }
+        }
+        for (((hash, version), (txn, info, events, write_set)), root_hash) in
+            committed.into_iter().zip(txns).zip(root_hashes)
+        {
+            let txn: TransactionOnChainData =
+                (version, txn, info, events, root_hash, write_set).into();
+            if let Some(cache) = &self.txn_by_hash_cache {
+                cache.lock().put(hash, txn.clone());
+            }
+            found.insert(hash, Some(txn));
+        }
+        Ok(hashes.iter().map(|hash| found[hash].clone()).collect())
+    }
+
+    /// Looks a transaction up in the DB and, failing that, in mempool, telling pending
+    /// transactions apart from ones this node has never seen
+    pub async fn lookup_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: u64,
+    ) -> Result<TransactionLookup> {
+        if let Some(txn) = self.get_transaction_by_hash(hash, ledger_version)? {
+            return Ok(TransactionLookup::Committed(Box::new(txn)));
+        }
+        Ok(match self.get_pending_transaction_by_hash(hash).await? {
+            Some(txn) => TransactionLookup::Pending(Box::new(txn)),
+            None => TransactionLookup::Unknown,
+        })
+    }
+
+    /// Retrieves a transaction by hash along with the timestamp of the block it was committed in
+    pub fn get_transaction_by_hash_with_timestamp(
+        &self,
+        hash: HashValue,
+        ledger_version: u64,
+    ) -> Result<Option<(TransactionOnChainData, u64)>> {
+        self.get_transaction_by_hash(hash, ledger_version)?
+            .map(|txn| {
+                // Versions before the first block metadata (i.e. genesis) resolve to timestamp 0
+                let timestamp = self.get_block_timestamp(txn.version)?;
+                Ok((txn, timestamp))
+            })
+            .transpose()
+    }
+
+    pub async fn get_pending_transaction_by_hash(
+        &self,
+        hash: HashValue,
+    ) -> Result<Option<SignedTransaction>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetTransactionByHash(hash, req_sender))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
+    /// Asks mempool how full it is, updating the mempool queue gauges along the way. Mempool
+    /// has the configured `health_check_mempool_timeout_ms` to answer.
+    pub async fn get_mempool_status(&self) -> Result<MempoolQueueStatus> {
+        let timeout = Duration::from_millis(self.node_config.api.health_check_mempool_timeout_ms());
+        let status = tokio::time::timeout(timeout, async {
+            let (req_sender, callback) = oneshot::channel();
+            self.mp_sender
+                .clone()
+                .send(MempoolClientRequest::GetQueueStatus(req_sender))
+                .await
+                .map_err(anyhow::Error::from)?;
+            callback.await.map_err(anyhow::Error::from)
+        })
+        .await
+        .map_err(|_| format_err!("Mempool didn't respond within {}ms", timeout.as_millis()))??;
+        metrics::MEMPOOL_QUEUE_SIZE.set(status.size as i64);
+        metrics::MEMPOOL_QUEUE_CAPACITY.set(status.capacity as i64);
+        Ok(status)
+    }
+
+    /// Returns the genesis transaction, at version 0, along with the write set it applied. Fails
+    /// with `VersionUnavailable::Pruned` once the pruner has removed it.
+    pub fn get_genesis_transaction(&self) -> Result<TransactionOnChainData> {
+        let ledger_version = self
+            .get_latest_ledger_info_with_signatures()?
+            .ledger_info()
+            .version();
+        let txn = self
+            .get_transaction_by_version(0, ledger_version)
+            .context("Genesis transaction is unavailable")?;
+        ensure!(
+            matches!(txn.transaction, Transaction::GenesisTransaction(_)),
+            "Transaction at version 0 is not a genesis transaction"
+        );
+        Ok(txn)
+    }
+
+    /// Retrieves the transaction at `version`. Versions beyond `ledger_version` or already
+    /// pruned fail with a `VersionUnavailable` error.
+    pub fn get_transaction_by_version(
+        &self,
+        version: u64,
+        ledger_version: u64,
+    ) -> Result<TransactionOnChainData> {
+        let oldest_version = self
+            .db
+            .get_first_txn_version()?
+            .ok_or_else(|| format_err!("Failed to retrieve oldest version"))?;
+        VersionUnavailable::check(version, oldest_version, ledger_version)?;
+        let (txn, info, events, write_set) = self
+            .get_raw_transactions(version, 1, ledger_version, true)?
+            .pop()
+            .ok_or_else(|| format_err!("transaction not found for version {}", version))?;
+        let accumulator_root_hash = self.get_accumulator_root_hash(version)?;
+        Ok((version, txn, info, events, accumulator_root_hash, write_set).into())
+    }
+
+    pub fn get_transaction_by_version_poem<E: NotFoundError + GoneError + InternalError>(
+        &self,
+        version: u64,
+        ledger_version: u64,
+    ) -> Result<TransactionOnChainData, E> {
+        self.get_transaction_by_version(version, ledger_version)
+            .map_err(|e| match e.downcast_ref::<VersionUnavailable>() {
+                Some(VersionUnavailable::NotFound { .. }) => E::not_found(e)
+                    .error_code(AptosErrorCode::VersionNotFound)
+                    .aptos_ledger_version(ledger_version),
+                Some(VersionUnavailable::Pruned { .. }) => E::gone(e)
+                    .error_code(AptosErrorCode::VersionPruned)
+                    .aptos_ledger_version(ledger_version),
+                None => E::internal(e.context("Failed to retrieve transaction"))
+                    .error_code(AptosErrorCode::ReadFromStorageError),
+            })
+    }
+
+    /// Same as `get_transaction_by_version`, in the requested `format`
+    pub fn get_transaction_by_version_formatted(
+        &self,
+        version: u64,
+        ledger_version: u64,
+        format: ResponseFormat,
+    ) -> Result<FormattedResponse<TransactionOnChainData>> {
+        FormattedResponse::new(
+            self.get_transaction_by_version(version, ledger_version)?,
+            format,
+        )
+    }
+
+    /// Same as `get_transactions`, in the requested `format`
+    pub fn get_transactions_formatted(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+        format: ResponseFormat,
+    ) -> Result<FormattedResponse<Vec<TransactionOnChainData>>> {
+        FormattedResponse::new(
+            self.get_transactions(start_version, limit, ledger_version)?,
+            format,
+        )
+    }
+
+    pub fn get_accumulator_root_hash(&self, version: u64) -> Result<HashValue> {
+        self.db.get_accumulator_root_hash(version)
+    }
+
+    /// Spot-checks the DB for corruption around `version`: re-derives the transaction
+    /// accumulator root hash of each of the last `CONSISTENCY_CHECK_WINDOW` versions up to
+    /// `version` from their transaction infos, and compares it to the one the DB has stored.
+    ///
+    /// This reads a consistency proof and a page of transaction infos, then hashes every
+    /// version of the window, so it's meant for operators debugging a node rather than clients.
+    pub fn verify_version_consistency(&self, version: u64) -> Result<ConsistencyReport> {
+        let ledger_version = self
+            .get_latest_ledger_info_with_signatures()?
+            .ledger_info()
+            .version();
+        let oldest_version = self.db.get_first_txn_version()?.unwrap_or(0);
+        VersionUnavailable::check(version, oldest_version, ledger_version)?;
+        let start_version = version
+            .saturating_sub(CONSISTENCY_CHECK_WINDOW - 1)
+            .max(oldest_version);
+
+        // The accumulator up to `start_version` is rebuilt from its frozen subtrees, which the DB
+        // derives from its accumulator nodes rather than from the transaction infos themselves
+        let mut accumulator = if start_version == 0 {
+            InMemoryAccumulator::<TransactionAccumulatorHasher>::default()
+        } else {
+            InMemoryAccumulator::new(
+                self.db
+                    .get_accumulator_consistency_proof(None, start_version - 1)?
+                    .into_subtrees(),
+                start_version,
+            )?
+        };
+        let infos = self
+            .db
+            .get_transactions(
+                start_version,
+                version - start_version + 1,
+                ledger_version,
+                false,
+            )?
+            .proof
+            .transaction_infos;
+
+        let mut mismatches = vec![];
+        for (i, info) in infos.iter().enumerate() {
+            let info_version = start_version + i as u64;
+            accumulator = accumulator.append(&[info.hash()]);
+            let stored = self.get_accumulator_root_hash(info_version)?;
+            if accumulator.root_hash() != stored {
+                mismatches.push(AccumulatorMismatch {
+                    version: info_version,
+                    stored,
+                    derived: accumulator.root_hash(),
+                });
+            }
+        }
+        Ok(ConsistencyReport {
+            start_version,
+            end_version: version,
+            mismatches,
+        })
+    }
+
+    fn convert_into_transaction_on_chain_data(
+        &self,
+        txn: TransactionWithProof,
+    ) -> Result<TransactionOnChainData> {
+        // the type is Vec<(Transaction, TransactionOutput)> - given we have one transaction here, there should only ever be one value in this array
+        let (_, txn_output) = &self
+            .db
+            .get_transaction_outputs(txn.version, 1, txn.version)?
+            .transactions_and_outputs[0];
+        self.get_accumulator_root_hash(txn.version)
+            .map(|h| (txn, h, txn_output).into())
+    }
+
+    /// Same as `get_events`, along with the total number of events under the handle so callers
+    /// can render pagination in one round trip
+    pub fn get_events_page(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<EventsPage> {
+        let events = self.get_events(event_key, start, limit, ledger_version)?;
+        let total = self.get_event_count(event_key, ledger_version)?;
+        let has_more = start.saturating_add(events.len() as u64) < total;
+        Ok(EventsPage {
+            events,
+            total,
+            has_more,
+        })
+    }
+
+    /// Returns the first `limit` events of several event handles merged in ascending
+    /// transaction version order, such as the deposit and withdraw events of an account. Events
+    /// of the same transaction are ordered by handle, in the order of `event_keys`.
+    ///
+    /// Each handle is read lazily in small batches, so handles whose events come late in the
+    /// merged order aren't read further than needed.
+    pub fn get_events_merged(
+        &self,
+        event_keys: &[EventKey],
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<ContractEvent>> {
+        if event_keys.is_empty() || limit == 0 {
+            return Ok(vec![]);
+        }
+        // Batches must not be clamped by `max_events_page_size`, or a full batch would look short
+        let batch_size = ((limit as usize + event_keys.len() - 1) / event_keys.len())
+            .min(self.node_config.api.max_events_page_size() as usize)
+            .max(1) as u16;
+        let mut cursors: Vec<_> = event_keys
+            .iter()
+            .map(|event_key| MergeCursor {
+                event_key,
+                next_sequence_number: 0,
+                buffered: VecDeque::new(),
+                exhausted: false,
+            })
+            .collect();
+
+        let mut heads = BinaryHeap::new();
+        for (index, cursor) in cursors.iter_mut().enumerate() {
+            if let Some(version) = cursor.peek(self, batch_size, ledger_version)? {
+                heads.push(Reverse((version, index)));
+            }
+        }
+        let mut events = Vec::with_capacity(limit as usize);
+        while events.len() < limit as usize {
+            let Reverse((_, index)) = match heads.pop() {
+                Some(head) => head,
+                None => break,
+            };
+            let cursor = &mut cursors[index];
+            events.extend(cursor.buffered.pop_front().map(|event| event.event));
+            if let Some(version) = cursor.peek(self, batch_size, ledger_version)? {
+                heads.push(Reverse((version, index)));
+            }
+        }
+        Ok(events)
+    }
+
+    /// Same as `get_events`, but only returns the events of type `type_tag`.
+    ///
+    /// Events are filtered after being read, so `limit` bounds the number of events scanned
+    /// starting at `start`, not the number returned. Callers paging through a handle should
+    /// advance `start` by `limit` regardless of how many events come back.
+    pub fn get_events_by_type(
+        &self,
+        event_key: &EventKey,
+        type_tag: &TypeTag,
+        start: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<ContractEvent>> {
+        Ok(self
+            .get_events(event_key, start, limit, ledger_version)?
+            .into_iter()
+            .filter(|event| event.type_tag() == type_tag)
+            .collect())
+    }
+
+    pub fn get_events(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<ContractEvent>> {
+        Ok(self
+            .get_events_with_versions(event_key, start, limit, ledger_version)?
+            .into_iter()
+            .map(|event| event.event)
+            .collect::<Vec<_>>())
     }
 
-    pub fn get_accumulator_root_hash(&self, version: u64) -> Result<HashValue> {
-        self.db.get_accumulator_root_hash(version)
+    /// Same as `get_events`, with the data of each event decoded into the JSON the events
+    /// routes render, using the types as of `ledger_version`. Events whose type can't be
+    /// resolved, e.g. because its module is gone, are returned with just their raw bytes.
+    pub fn get_events_decoded(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<DecodedEvent>> {
+        let events = self.get_events(event_key, start, limit, ledger_version)?;
+        self.with_converter(ledger_version, |converter| {
+            Ok(events
+                .into_iter()
+                .map(|event| {
+                    let data = converter
+                        .try_into_events(std::slice::from_ref(&event))
+                        .ok()
+                        .and_then(|mut decoded| decoded.pop())
+                        .map(|decoded| decoded.data);
+                    DecodedEvent {
+                        key: *event.key(),
+                        sequence_number: event.sequence_number(),
+                        type_tag: event.type_tag().clone(),
+                        data,
+                        bytes: event.event_data().to_vec(),
+                    }
+                })
+                .collect())
+        })
     }
 
-    fn convert_into_transaction_on_chain_data(
+    /// Reads the events of the handle `address` created as its `creation_number`th GUID, see
+    /// `derive_event_key`, so callers don't need to construct the `EventKey` themselves
+    pub fn get_account_events(
         &self,
-        txn: TransactionWithProof,
-    ) -> Result<TransactionOnChainData> {
-        // the type is Vec<(Transaction, TransactionOutput)> - given we have one transaction here, there should only ever be one value in this array
-        let (_, txn_output) = &self
+        address: AccountAddress,
+        creation_number: u64,
+        start: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<ContractEvent>> {
+        let event_key = derive_event_key(creation_number, address);
+        ensure!(
+            event_key.get_creator_address() == address
+                && event_key.get_creation_number() == creation_number,
+            "malformed event key {} derived from address {} and creation number {}",
+            event_key,
+            address,
+            creation_number
+        );
+        self.get_events(&event_key, start, limit, ledger_version)
+    }
+
+    /// Returns the `limit` most recent events of an event handle as of `ledger_version`, newest
+    /// first. The newest sequence number is read from the handle's counter, so callers don't
+    /// need to know it upfront. Handles with fewer than `limit` events return all of them.
+    pub fn get_latest_events(
+        &self,
+        event_key: &EventKey,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<ContractEvent>> {
+        let limit = self.clamp_limit(
+            "get_latest_events",
+            limit,
+            self.node_config.api.max_events_page_size(),
+        );
+        let _timer = self.slow_query_timer(
+            SlowQueryLog::new("get_latest_events")
+                .limit(limit)
+                .ledger_version(ledger_version),
+        );
+        let count = self.get_event_count(event_key, ledger_version)?;
+        if count == 0 || limit == 0 {
+            return Ok(vec![]);
+        }
+        let events = self
             .db
-            .get_transaction_outputs(txn.version, 1, txn.version)?
-            .transactions_and_outputs[0];
-        self.get_accumulator_root_hash(txn.version)
-            .map(|h| (txn, h, txn_output).into())
+            .get_events(event_key, count - 1, Order::Descending, limit as u64)?;
+        Ok(events
+            .into_iter()
+            .filter(|event| event.transaction_version <= ledger_version)
+            .map(|event| event.event)
+            .collect())
     }
 
-    pub fn get_events(
+    /// Same as `get_events`, in the requested `format`
+    pub fn get_events_formatted(
         &self,
         event_key: &EventKey,
         start: u64,
         limit: u16,
         ledger_version: u64,
-    ) -> Result<Vec<ContractEvent>> {
+        format: ResponseFormat,
+    ) -> Result<FormattedResponse<Vec<ContractEvent>>> {
+        FormattedResponse::new(
+            self.get_events(event_key, start, limit, ledger_version)?,
+            format,
+        )
+    }
+
+    /// Retrieves events along with the hash of the transaction that emitted each of them
+    pub fn get_events_with_txn_hash(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<(ContractEvent, HashValue)>> {
+        let events = self.get_events_with_versions(event_key, start, limit, ledger_version)?;
+
+        // Several events are often emitted by the same transaction, and events of a page are
+        // usually close together, so the infos of the whole span of versions are read at once.
+        // Spans too wide for that have the info of each distinct version read on its own.
+        let versions: BTreeSet<u64> = events
+            .iter()
+            .map(|event| event.transaction_version)
+            .collect();
+        let txn_hashes = match (versions.iter().next(), versions.iter().next_back()) {
+            (Some(&first), Some(&last)) if last - first < MAX_EVENT_TXN_INFOS_SPAN => {
+                let infos =
+                    self.db
+                        .get_transaction_infos(first, last - first + 1, ledger_version)?;
+                (first..)
+                    .zip(infos)
+                    .filter(|(version, _)| versions.contains(version))
+                    .map(|(version, info)| (version, info.transaction_hash()))
+                    .collect::<HashMap<_, _>>()
+            }
+            _ => versions
+                .iter()
+                .map(|&version| {
+                    let info = self
+                        .db
+                        .get_transaction_infos(version, 1, ledger_version)?
+                        .pop()
+                        .ok_or_else(|| format_err!("No transaction info at version {}", version))?;
+                    Ok((version, info.transaction_hash()))
+                })
+                .collect::<Result<HashMap<_, _>>>()?,
+        };
+
+        Ok(events
+            .into_iter()
+            .map(|event| (event.event, txn_hashes[&event.transaction_version]))
+            .collect())
+    }
+
+    fn get_events_with_versions(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<EventWithVersion>> {
+        let limit = self.clamp_limit(
+            "get_events",
+            limit,
+            self.node_config.api.max_events_page_size(),
+        );
+        let _timer = self.slow_query_timer(
+            SlowQueryLog::new("get_events")
+                .start(start)
+                .limit(limit)
+                .ledger_version(ledger_version),
+        );
         let events = self
             .db
             .get_events(event_key, start, Order::Ascending, limit as u64)?;
         Ok(events
             .into_iter()
             .filter(|event| event.transaction_version <= ledger_version)
-            .map(|event| event.event)
             .collect::<Vec<_>>())
     }
 
     pub fn health_check_route(&self) -> BoxedFilter<(impl Reply,)> {
-        super::health_check::health_check_route(self.db.clone())
+        super::health_check::health_check_route(self.clone())
+    }
+
+    /// Checks that mempool answers a lookup of the zero hash within the configured
+    /// `health_check_mempool_timeout_ms`
+    pub async fn probe_mempool(&self) -> Result<()> {
+        let timeout = Duration::from_millis(self.node_config.api.health_check_mempool_timeout_ms());
+        tokio::time::timeout(
+            timeout,
+            self.get_pending_transaction_by_hash(HashValue::zero()),
+        )
+        .await
+        .map_err(|_| format_err!("Mempool didn't respond within {}ms", timeout.as_millis()))??;
+        Ok(())
+    }
+}
+
+/// Options for `Context::simulate_transaction_with_options`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SimulationOptions {
+    /// Simulate with this `max_gas_amount` instead of the transaction's own
+    pub override_max_gas: Option<u64>,
+    /// Don't reject transactions carrying a valid signature. The VM still refuses to simulate
+    /// them, unless the signature no longer matches because `override_max_gas` changed the
+    /// transaction.
+    pub skip_signature_check: bool,
+}
+
+/// Result of simulating a transaction
+#[derive(Debug)]
+pub struct SimulationOutcome {
+    pub status: VMStatus,
+    pub gas_used: u64,
+    pub output: TransactionOutput,
+}
+
+/// How a committed transaction went, see `Context::get_transaction_status`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionStatusSummary {
+    pub version: u64,
+    /// Whether a user sent the transaction, rather than it being run by the system
+    pub is_user_transaction: bool,
+    pub success: bool,
+    /// The execution status, carrying the abort or VM status code of a failed transaction
+    pub status: ExecutionStatus,
+    pub gas_used: u64,
+}
+
+/// Metadata about a state entry, see `Context::get_state_values_with_metadata`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateValueMetadata {
+    /// Version of the transaction that last wrote the entry
+    pub last_modified_version: u64,
+}
+
+/// Result of `Context::replay_transaction`
+#[derive(Clone, Debug)]
+pub struct ReplayedTransaction {
+    pub data: TransactionOnChainData,
+    /// Whether the recomputed write set is the one that was committed
+    pub write_set_matches: bool,
+}
+
+/// Result of `Context::lookup_transaction_by_hash`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionLookup {
+    /// Committed at or before the requested ledger version
+    Committed(Box<TransactionOnChainData>),
+    /// Not committed yet, but waiting in mempool
+    Pending(Box<SignedTransaction>),
+    /// Neither committed nor in mempool, e.g. never submitted to this node or already evicted
+    Unknown,
+}
+
+/// Returned by `Context::submit_and_wait` when a transaction isn't committed in time
+#[derive(Debug)]
+pub struct TransactionWaitTimeout {
+    pub hash: HashValue,
+    pub timeout: Duration,
+    /// Whether the transaction was still in mempool when the wait gave up
+    pub pending_in_mempool: bool,
+}
+
+impl fmt::Display for TransactionWaitTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Transaction {} was not committed within {:?}",
+            self.hash, self.timeout
+        )?;
+        if self.pending_in_mempool {
+            write!(f, ", it is still pending in mempool")
+        } else {
+            write!(f, ", it is no longer in mempool")
+        }
+    }
+}
+
+impl std::error::Error for TransactionWaitTimeout {}
+
+/// Returned by `Context::submit_and_wait` when its cancellation token is cancelled
+#[derive(Debug)]
+pub struct TransactionWaitCancelled {
+    pub hash: HashValue,
+}
+
+impl fmt::Display for TransactionWaitCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Waiting for transaction {} was cancelled", self.hash)
+    }
+}
+
+impl std::error::Error for TransactionWaitCancelled {}
+
+/// The format `Context` methods with a `_formatted` variant return their data in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResponseFormat {
+    /// The data as is, for callers that convert it to JSON
+    Json,
+    /// The data serialized as BCS, for clients that deserialize it directly
+    Bcs,
+}
+
+impl From<&AcceptType> for ResponseFormat {
+    fn from(accept_type: &AcceptType) -> Self {
+        match accept_type {
+            AcceptType::Json => ResponseFormat::Json,
+            AcceptType::Bcs => ResponseFormat::Bcs,
+        }
+    }
+}
+
+/// Data returned in a `ResponseFormat`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FormattedResponse<T> {
+    Json(T),
+    Bcs(Vec<u8>),
+}
+
+impl<T: Serialize> FormattedResponse<T> {
+    pub fn new(value: T, format: ResponseFormat) -> Result<Self> {
+        Ok(match format {
+            ResponseFormat::Json => FormattedResponse::Json(value),
+            ResponseFormat::Bcs => FormattedResponse::Bcs(bcs::to_bytes(&value)?),
+        })
+    }
+}
+
+/// Result of `Context::submit_transaction_typed`, with the common rejection reasons broken out
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubmissionOutcome {
+    Accepted,
+    MempoolIsFull,
+    SequenceNumberTooOld,
+    SequenceNumberTooNew,
+    InsufficientBalanceForTransactionFee,
+    TransactionExpired,
+    InvalidSignature,
+    TooManyTransactionsForAccount,
+    /// Any other rejection, as reported by mempool and the VM
+    Rejected {
+        code: MempoolStatusCode,
+        vm_status: Option<DiscardedVMStatus>,
+        reason: String,
+    },
+}
+
+impl SubmissionOutcome {
+    /// The error code to report the outcome with, `None` if the transaction was accepted
+    pub fn error_code(&self) -> Option<AptosErrorCode> {
+        Some(match self {
+            SubmissionOutcome::Accepted => return None,
+            SubmissionOutcome::MempoolIsFull => AptosErrorCode::MempoolIsFull,
+            SubmissionOutcome::SequenceNumberTooOld => AptosErrorCode::SequenceNumberTooOld,
+            SubmissionOutcome::SequenceNumberTooNew => AptosErrorCode::SequenceNumberTooNew,
+            SubmissionOutcome::InsufficientBalanceForTransactionFee => {
+                AptosErrorCode::InsufficientBalanceForTransactionFee
+            }
+            SubmissionOutcome::TransactionExpired => AptosErrorCode::TransactionExpired,
+            SubmissionOutcome::InvalidSignature => AptosErrorCode::InvalidTransactionSignature,
+            SubmissionOutcome::TooManyTransactionsForAccount => {
+                AptosErrorCode::TooManyTransactionsForAccount
+            }
+            SubmissionOutcome::Rejected { .. } => AptosErrorCode::TransactionRejected,
+        })
+    }
+}
+
+impl From<SubmissionStatus> for SubmissionOutcome {
+    fn from((mempool_status, vm_status): SubmissionStatus) -> Self {
+        match (mempool_status.code, vm_status) {
+            (MempoolStatusCode::Accepted, _) => SubmissionOutcome::Accepted,
+            (MempoolStatusCode::MempoolIsFull, _) => SubmissionOutcome::MempoolIsFull,
+            (MempoolStatusCode::TooManyTransactions, _) => {
+                SubmissionOutcome::TooManyTransactionsForAccount
+            }
+            // Mempool rejects sequence numbers older than the account's without asking the VM
+            (MempoolStatusCode::InvalidSeqNumber, _)
+            | (_, Some(DiscardedVMStatus::SEQUENCE_NUMBER_TOO_OLD)) => {
+                SubmissionOutcome::SequenceNumberTooOld
+            }
+            (_, Some(DiscardedVMStatus::SEQUENCE_NUMBER_TOO_NEW)) => {
+                SubmissionOutcome::SequenceNumberTooNew
+            }
+            (_, Some(DiscardedVMStatus::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE)) => {
+                SubmissionOutcome::InsufficientBalanceForTransactionFee
+            }
+            (_, Some(DiscardedVMStatus::TRANSACTION_EXPIRED)) => {
+                SubmissionOutcome::TransactionExpired
+            }
+            (_, Some(DiscardedVMStatus::INVALID_SIGNATURE)) => SubmissionOutcome::InvalidSignature,
+            (code, vm_status) => SubmissionOutcome::Rejected {
+                code,
+                vm_status,
+                reason: mempool_status.message,
+            },
+        }
+    }
+}
+
+/// Returned by `Context::submit_transaction` for transactions over the size limit
+#[derive(Clone, Debug)]
+pub struct TransactionTooLarge {
+    pub size: u64,
+    pub limit: u64,
+}
+
+impl fmt::Display for TransactionTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Transaction of {} bytes exceeds the limit of {} bytes",
+            self.size, self.limit
+        )
+    }
+}
+
+impl std::error::Error for TransactionTooLarge {}
+
+/// Returned by `Context::submit_transaction_bcs` for bytes that aren't a signed transaction
+#[derive(Clone, Debug)]
+pub struct InvalidTransactionEncoding {
+    pub error: String,
+}
+
+impl fmt::Display for InvalidTransactionEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid BCS encoded signed transaction: {}", self.error)
+    }
+}
+
+impl std::error::Error for InvalidTransactionEncoding {}
+
+/// Returned by `Context::read_with_timeout` when a read runs for too long
+#[derive(Clone, Debug)]
+pub struct ReadTimeout {
+    pub timeout: Duration,
+}
+
+impl fmt::Display for ReadTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Read did not complete within {:?}", self.timeout)
+    }
+}
+
+impl std::error::Error for ReadTimeout {}
+
+/// Returned by `Context::acquire_heavy_read` when every heavy read permit is in use
+#[derive(Clone, Debug)]
+pub struct Overloaded {
+    pub limit: u64,
+}
+
+impl fmt::Display for Overloaded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Node is serving the maximum of {} heavy reads, retry later",
+            self.limit
+        )
+    }
+}
+
+impl std::error::Error for Overloaded {}
+
+/// Returned when a read would return more entries than the node is configured to return at
+/// once, see `Context::get_state_values`
+#[derive(Clone, Debug)]
+pub struct ResponseTooLarge {
+    pub count: u64,
+    pub limit: u64,
+}
+
+impl fmt::Display for ResponseTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Response would hold at least {} entries, more than the maximum of {}",
+            self.count, self.limit
+        )
+    }
+}
+
+impl std::error::Error for ResponseTooLarge {}
+
+/// Returned when the estimated size of a page of transactions is over the configured maximum,
+/// see `Context::check_transactions_response_size`
+#[derive(Clone, Debug)]
+pub struct ResponseSizeExceeded {
+    pub estimated_size: u64,
+    pub limit: u64,
+}
+
+impl fmt::Display for ResponseSizeExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Response would take about {} bytes, more than the maximum of {}, request fewer \
+             transactions",
+            self.estimated_size, self.limit
+        )
+    }
+}
+
+impl std::error::Error for ResponseSizeExceeded {}
+
+/// A heavy read permit taken by `Context::acquire_heavy_read`, released on drop
+pub struct HeavyReadPermit<'a> {
+    _permit: SemaphorePermit<'a>,
+}
+
+impl Drop for HeavyReadPermit<'_> {
+    fn drop(&mut self) {
+        metrics::HEAVY_READS_IN_FLIGHT.dec();
+    }
+}
+
+/// An event with its data decoded, see `Context::get_events_decoded`
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedEvent {
+    pub key: EventKey,
+    pub sequence_number: u64,
+    pub type_tag: TypeTag,
+    /// The data as JSON, `None` if the event type couldn't be resolved
+    pub data: Option<serde_json::Value>,
+    /// The BCS encoded data as stored
+    pub bytes: Vec<u8>,
+}
+
+impl DecodedEvent {
+    pub fn is_decoded(&self) -> bool {
+        self.data.is_some()
+    }
+}
+
+/// Returned by `Context::decode_entry_function`
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodedEntryFunction {
+    EntryFunction {
+        function: ScriptFunctionId,
+        type_arguments: Vec<TypeTag>,
+        arguments: Vec<DecodedArgument>,
+    },
+    NotAnEntryFunction,
+}
+
+/// An argument of a decoded function call
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedArgument {
+    /// The BCS bytes the transaction passed
+    pub bytes: Vec<u8>,
+    /// The decoded value, if the parameter's type could be resolved
+    pub value: Option<MoveValue>,
+}
+
+/// How far behind the latest version `Context::get_account_state_bounded_staleness` may read
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Staleness {
+    Versions(u64),
+    /// Measured between the block timestamps of the two versions
+    Duration(Duration),
+}
+
+/// Returned by `Context::get_liveness_info`
+#[derive(Clone, Debug)]
+pub struct LivenessInfo {
+    pub ledger_version: u64,
+    /// Timestamp of the block of `ledger_version`
+    pub block_timestamp_usecs: u64,
+    /// How far the block timestamp is behind the local clock
+    pub lag: Duration,
+}
+
+/// Returned by `Context::node_info`
+#[derive(Clone, Debug)]
+pub struct NodeInfo {
+    pub role: RoleType,
+    /// Whether the API is served by a validator rather than a fullnode
+    pub is_validator: bool,
+    pub chain_id: ChainId,
+    /// Version of the API crate the node was built with
+    pub api_version: &'static str,
+    /// Commit the node was built from, if the build recorded it
+    pub git_sha: Option<&'static str>,
+}
+
+/// Result of `Context::verify_version_consistency` over `start_version..=end_version`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    pub start_version: u64,
+    pub end_version: u64,
+    /// Versions whose stored accumulator root hash doesn't match the one derived from the
+    /// transaction infos, empty if the range is consistent
+    pub mismatches: Vec<AccumulatorMismatch>,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccumulatorMismatch {
+    pub version: u64,
+    pub stored: HashValue,
+    pub derived: HashValue,
+}
+
+// Caches committed blocks by height, and indexes them by start version so that the block
+// holding a version is found with a range query instead of a scan of the whole cache
+struct BlockInfoCache {
+    blocks: LruCache<u64, BlockInfo>,
+    heights: BTreeMap<u64, u64>,
+}
+
+impl BlockInfoCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            blocks: LruCache::new(capacity),
+            heights: BTreeMap::new(),
+        }
+    }
+
+    fn put(&mut self, block_info: BlockInfo) {
+        self.heights
+            .insert(block_info.start_version, block_info.block_height);
+        if let Some((_, evicted)) = self.blocks.push(block_info.block_height, block_info) {
+            if evicted.start_version != block_info.start_version {
+                self.heights.remove(&evicted.start_version);
+            }
+        }
+    }
+
+    fn get(&mut self, height: u64) -> Option<BlockInfo> {
+        self.blocks.get(&height).copied()
+    }
+
+    // The cached block starting at or closest before `version`
+    fn closest_at_or_before(&mut self, version: u64) -> Option<BlockInfo> {
+        let (_, height) = self.heights.range(..=version).next_back()?;
+        self.blocks.get(height).copied()
+    }
+
+    fn containing(&mut self, version: u64) -> Option<BlockInfo> {
+        self.closest_at_or_before(version)
+            .filter(|block_info| version <= block_info.end_version)
+    }
+}
+
+// Reads one event handle for `Context::get_events_merged`, a batch at a time
+struct MergeCursor<'a> {
+    event_key: &'a EventKey,
+    next_sequence_number: u64,
+    buffered: VecDeque<EventWithVersion>,
+    exhausted: bool,
+}
+
+impl MergeCursor<'_> {
+    // Returns the transaction version of the next event, reading a batch if none is buffered
+    fn peek(
+        &mut self,
+        context: &Context,
+        batch_size: u16,
+        ledger_version: u64,
+    ) -> Result<Option<u64>> {
+        if self.buffered.is_empty() && !self.exhausted {
+            let batch = context.get_events_with_versions(
+                self.event_key,
+                self.next_sequence_number,
+                batch_size,
+                ledger_version,
+            )?;
+            // Events beyond the ledger version are filtered out, and later events can only be
+            // further beyond it, so a short batch means the handle has nothing more to give
+            self.exhausted = batch.len() < batch_size as usize;
+            self.next_sequence_number += batch.len() as u64;
+            self.buffered.extend(batch);
+        }
+        Ok(self.buffered.front().map(|event| event.transaction_version))
+    }
+}
+
+/// A page of events returned by `Context::get_events_page`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventsPage {
+    pub events: Vec<ContractEvent>,
+    /// Number of events emitted under the handle as of the ledger version
+    pub total: u64,
+    pub has_more: bool,
+}
+
+/// The accounts involved in a transaction, see `extract_participants`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParticipantSet {
+    /// The sender of a user transaction, `None` for system transactions
+    pub sender: Option<AccountAddress>,
+    /// Accounts that received coins in the transaction
+    pub receivers: BTreeSet<AccountAddress>,
+}
+
+// Substitutes a function's type arguments into the type of one of its parameters
+fn instantiate_type(typ: &MoveType, ty_args: &[TypeTag]) -> Result<TypeTag> {
+    match typ {
+        MoveType::GenericTypeParam { index } => ty_args
+            .get(*index as usize)
+            .cloned()
+            .ok_or_else(|| format_err!("Missing type argument T{}", index)),
+        MoveType::Vector { items } => {
+            Ok(TypeTag::Vector(Box::new(instantiate_type(items, ty_args)?)))
+        }
+        _ => typ.clone().try_into(),
+    }
+}
+
+/// Returns the key of the event handle that `address` created as its `creation_number`th GUID,
+/// which is what `Context::get_events` and friends read events by.
+pub fn derive_event_key(creation_number: u64, address: AccountAddress) -> EventKey {
+    EventKey::new(creation_number, address)
+}
+
+/// Extracts the sender and coin transfer recipients of a transaction.
+///
+/// Only user transactions have a sender. Recipients are recognized from `0x1::coin::DepositEvent`
+/// events, whose handle is owned by the account receiving the coins, so any coin type and any
+/// payload that deposits coins is covered. Transactions that emit no deposit events, e.g. with
+/// payloads moving value in other ways, yield just the sender.
+pub fn extract_participants(txn: &TransactionOnChainData) -> ParticipantSet {
+    let sender = match &txn.transaction {
+        Transaction::UserTransaction(signed_txn) => Some(signed_txn.sender()),
+        _ => None,
+    };
+    let receivers = txn
+        .events
+        .iter()
+        .filter(|event| is_coin_deposit_event(event.type_tag()))
+        .map(|event| event.key().get_creator_address())
+        .collect();
+    ParticipantSet { sender, receivers }
+}
+
+// Returns a key sorting between `start` and `end`, or after `start` if there's no `end`, by
+// averaging their paths. Keys sort by the length of their path first, so paths of different
+// lengths aren't averaged, the midpoint is taken towards the last path of `start`'s length.
+fn midpoint_key(start: &StateKey, end: Option<&StateKey>) -> Option<StateKey> {
+    let start = match start {
+        StateKey::AccessPath(access_path) => access_path,
+        _ => return None,
+    };
+    let high = match end {
+        Some(StateKey::AccessPath(end)) if end.path.len() == start.path.len() => end.path.clone(),
+        _ => vec![u8::MAX; start.path.len()],
+    };
+    let path = midpoint(&start.path, &high);
+    (path != start.path).then(|| StateKey::AccessPath(AccessPath::new(start.address, path)))
+}
+
+// Averages two big endian numbers of the same length
+fn midpoint(low: &[u8], high: &[u8]) -> Vec<u8> {
+    let mut sum = vec![0u16; low.len()];
+    let mut carry = 0u16;
+    for i in (0..low.len()).rev() {
+        let byte = low[i] as u16 + high[i] as u16 + carry;
+        sum[i] = byte & 0xff;
+        carry = byte >> 8;
+    }
+    sum.into_iter()
+        .map(|byte| {
+            let byte = (carry << 8) | byte;
+            carry = byte & 1;
+            (byte >> 1) as u8
+        })
+        .collect()
+}
+
+fn is_coin_deposit_event(type_tag: &TypeTag) -> bool {
+    matches!(
+        type_tag,
+        TypeTag::Struct(tag) if tag.address == CORE_CODE_ADDRESS
+            && tag.module.as_str() == "coin"
+            && tag.name.as_str() == "DepositEvent"
+    )
+}
+
+/// Changes to an account's state between two versions, each sorted by state key
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    pub added: Vec<(StateKey, StateValue)>,
+    pub removed: Vec<(StateKey, StateValue)>,
+    /// Keys present at both versions with different values, as (key, before, after)
+    pub changed: Vec<(StateKey, StateValue, StateValue)>,
+}
+
+impl StateDiff {
+    /// Converts the diff into the writes that turn the old state into the new one
+    pub fn into_write_ops(self) -> Vec<(StateKey, WriteOp)> {
+        let written = self
+            .added
+            .into_iter()
+            .chain(self.changed.into_iter().map(|(key, _, value)| (key, value)))
+            .filter_map(|(key, value)| value.maybe_bytes.map(|bytes| (key, WriteOp::Value(bytes))));
+        let deleted = self
+            .removed
+            .into_iter()
+            .map(|(key, _)| (key, WriteOp::Deletion));
+        written.chain(deleted).collect()
+    }
+}
+
+/// Why a transaction version can't be read
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionUnavailable {
+    /// The version is beyond the ledger version
+    NotFound { version: u64, ledger_version: u64 },
+    /// The version is older than the oldest version kept by the pruner
+    Pruned { version: u64, oldest_version: u64 },
+}
+
+impl VersionUnavailable {
+    pub fn check(version: u64, oldest_version: u64, ledger_version: u64) -> Result<(), Self> {
+        if version > ledger_version {
+            Err(Self::NotFound {
+                version,
+                ledger_version,
+            })
+        } else if version < oldest_version {
+            Err(Self::Pruned {
+                version,
+                oldest_version,
+            })
+        } else {
+            Ok(())
+        }
     }
 }
 
+impl fmt::Display for VersionUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound {
+                version,
+                ledger_version,
+            } => write!(
+                f,
+                "Version {} not found, the ledger version is {}",
+                version, ledger_version
+            ),
+            Self::Pruned {
+                version,
+                oldest_version,
+            } => write!(
+                f,
+                "Version {} has been pruned, the oldest available version is {}",
+                version, oldest_version
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VersionUnavailable {}
+
+/// Categories of routes that accept a request body, used to pick a content length limit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteKind {
+    /// Routes that only read data, e.g. table item lookups
+    Read,
+    /// Transaction submission and signing message creation, which may carry module bundles
+    SubmitTransaction,
+    SimulateTransaction,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BlockMetadataState {
     epoch_internal: U64,
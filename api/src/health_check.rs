@@ -1,17 +1,17 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::context::Context;
 use anyhow::{ensure, Result};
 use std::{
     ops::Sub,
-    sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use storage_interface::DbReader;
 use warp::{filters::BoxedFilter, reject, Filter, Reply};
 
 // HealthCheckParams is optional params for different layer's health check.
 // If no param is provided, server return 200 by default to indicate HTTP server is running health.
+// Independently of the params, mempool is probed when `health_check_mempool_probe` is configured.
 #[derive(serde::Deserialize)]
 struct HealthCheckParams {
     // Health check returns 200 when this param is provided and meet the following condition:
@@ -23,11 +23,11 @@ struct HealthCheckParams {
 struct HealthCheckError;
 impl reject::Reject for HealthCheckError {}
 
-pub fn health_check_route(health_aptos_db: Arc<dyn DbReader>) -> BoxedFilter<(impl Reply,)> {
+pub fn health_check_route(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("-" / "healthy")
         .and(warp::path::end())
         .and(warp::query().map(move |params: HealthCheckParams| params))
-        .and(warp::any().map(move || health_aptos_db.clone()))
+        .and(context.filter())
         .and(warp::any().map(SystemTime::now))
         .and_then(health_check)
         .boxed()
@@ -35,18 +35,24 @@ pub fn health_check_route(health_aptos_db: Arc<dyn DbReader>) -> BoxedFilter<(im
 
 async fn health_check(
     params: HealthCheckParams,
-    db: Arc<dyn DbReader>,
+    context: Context,
     now: SystemTime,
 ) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
     if let Some(duration) = params.duration_secs {
-        let ledger_info = db
-            .get_latest_ledger_info()
+        let ledger_info = context
+            .get_latest_ledger_info_with_signatures()
             .map_err(|_| reject::custom(HealthCheckError))?;
         let timestamp = ledger_info.ledger_info().timestamp_usecs();
 
         check_latest_ledger_info_timestamp(duration, timestamp, now)
             .map_err(|_| reject::custom(HealthCheckError))?;
     }
+    if context.health_check_mempool_probe() {
+        context
+            .probe_mempool()
+            .await
+            .map_err(|_| reject::custom(HealthCheckError))?;
+    }
     Ok(Box::new("aptos-node:ok"))
 }
 
@@ -3,6 +3,8 @@
 
 mod accept_type;
 mod accounts;
+pub mod caching_db_reader;
+pub mod circuit_breaker;
 pub mod context;
 mod events;
 mod health_check;
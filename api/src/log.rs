@@ -1,19 +1,29 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use std::time::Duration;
+use std::{
+    cell::RefCell,
+    future::Future,
+    time::{Duration, Instant},
+};
 
 use aptos_logger::{
     debug, error,
     prelude::{sample, SampleRate},
     sample::Sampling,
-    Schema,
+    warn, Schema,
 };
+use aptos_types::account_address::AccountAddress;
+use futures::future::poll_fn;
 use warp::{
-    http::header,
+    http::{header, HeaderMap},
     log::{custom, Info, Log},
 };
 
+/// Header identifying a request, set by clients or the proxies in front of the node, that is
+/// logged as the trace id of its slow queries
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
 pub fn logger() -> Log<impl Fn(Info) + Copy> {
     let func = move |info: Info| {
         let status = info.status().as_u16();
@@ -52,3 +62,131 @@ pub struct HttpRequestLog<'a> {
     elapsed: std::time::Duration,
     forwarded: Option<&'a str>,
 }
+
+#[derive(Schema)]
+pub struct SlowQueryLog {
+    method: &'static str,
+    #[schema(debug)]
+    elapsed: Duration,
+    version: Option<u64>,
+    start: Option<u64>,
+    limit: Option<u16>,
+    ledger_version: Option<u64>,
+    #[schema(display)]
+    address: Option<AccountAddress>,
+    trace_id: Option<String>,
+}
+
+impl SlowQueryLog {
+    pub fn new(method: &'static str) -> Self {
+        Self {
+            method,
+            elapsed: Duration::ZERO,
+            version: None,
+            start: None,
+            limit: None,
+            ledger_version: None,
+            address: None,
+            trace_id: RequestContext::current().trace_id,
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT_REQUEST: RefCell<RequestContext> = RefCell::new(RequestContext::default());
+}
+
+/// Identifies the HTTP request a read is served for, so that the slow query logs of its reads
+/// can be correlated with it. Requests are served within `scope`, reads made outside of one have
+/// no trace id.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RequestContext {
+    pub trace_id: Option<String>,
+}
+
+impl RequestContext {
+    pub fn new(trace_id: impl Into<String>) -> Self {
+        Self {
+            trace_id: Some(trace_id.into()),
+        }
+    }
+
+    /// The request identified by the `X-Request-Id` header of `headers`, if any
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            trace_id: headers
+                .get(REQUEST_ID_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_owned),
+        }
+    }
+
+    /// The request the current thread is serving
+    pub fn current() -> Self {
+        CURRENT_REQUEST.with(|current| current.borrow().clone())
+    }
+
+    /// Makes this the current request of this thread until the guard is dropped, which restores
+    /// the previous one. It's per thread, so it doesn't follow a read onto another thread,
+    /// `Context::read_with_timeout` carries it over to the blocking pool.
+    ///
+    /// The guard must never be held across an `.await`: the task may be resumed on another
+    /// worker thread, and other tasks polled on this one in the meantime would be attributed to
+    /// this request. Async code uses `scope` instead.
+    pub fn enter(self) -> RequestContextGuard {
+        let previous = CURRENT_REQUEST.with(|current| current.replace(self));
+        RequestContextGuard { previous }
+    }
+
+    /// Runs `future` as this request. It's entered around each poll of `future` only, so it
+    /// follows the task from one worker thread to another, and is never left current on a
+    /// thread while the task is suspended.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        poll_fn(move |cx| {
+            let _request = self.clone().enter();
+            future.as_mut().poll(cx)
+        })
+        .await
+    }
+}
+
+pub struct RequestContextGuard {
+    previous: RequestContext,
+}
+
+impl Drop for RequestContextGuard {
+    fn drop(&mut self) {
+        let previous = std::mem::take(&mut self.previous);
+        CURRENT_REQUEST.with(|current| current.replace(previous));
+    }
+}
+
+// Times a query for as long as it's alive, logging the query when dropped if it took longer
+// than the threshold.
+pub struct SlowQueryTimer {
+    log: Option<SlowQueryLog>,
+    threshold: Duration,
+    start: Instant,
+}
+
+impl SlowQueryTimer {
+    pub fn new(log: SlowQueryLog, threshold: Duration) -> Self {
+        Self {
+            log: Some(log),
+            threshold,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for SlowQueryTimer {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        if elapsed >= self.threshold {
+            if let Some(log) = self.log.take() {
+                warn!(log.elapsed(elapsed));
+            }
+        }
+    }
+}
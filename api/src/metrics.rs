@@ -1,7 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_metrics_core::{register_histogram_vec, HistogramVec};
+use aptos_metrics_core::{
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    HistogramVec, IntCounter, IntCounterVec, IntGauge,
+};
 
 use once_cell::sync::Lazy;
 use warp::log::{custom, Info, Log};
@@ -24,6 +27,63 @@ pub static RESPONSE_STATUS: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static MEMPOOL_CIRCUIT_BREAKER_OPEN: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_api_mempool_circuit_breaker_open",
+        "Whether the mempool submission circuit breaker is open (1) or closed (0)"
+    )
+    .unwrap()
+});
+
+pub static MEMPOOL_CIRCUIT_BREAKER_REJECTIONS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_api_mempool_circuit_breaker_rejections",
+        "Number of submissions rejected because the mempool circuit breaker is open"
+    )
+    .unwrap()
+});
+
+pub static MEMPOOL_QUEUE_SIZE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_api_mempool_queue_size",
+        "Number of transactions in mempool, as last reported to the API"
+    )
+    .unwrap()
+});
+
+pub static MEMPOOL_QUEUE_CAPACITY: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_api_mempool_queue_capacity",
+        "Number of transactions mempool holds before rejecting new ones"
+    )
+    .unwrap()
+});
+
+pub static HEAVY_READS_IN_FLIGHT: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_api_heavy_reads_in_flight",
+        "Number of heavy reads (transaction pages, prefix scans) currently holding a permit"
+    )
+    .unwrap()
+});
+
+pub static HEAVY_READS_REJECTED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_api_heavy_reads_rejected",
+        "Number of heavy reads rejected because every permit was in use"
+    )
+    .unwrap()
+});
+
+pub static LIMIT_CLAMPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_api_limit_clamped",
+        "Number of reads whose limit was lowered to the configured maximum, by method",
+        &["method"]
+    )
+    .unwrap()
+});
+
 // Record metrics by method, operation_id and status.
 // The operation_id is the id for the request handler.
 // Should use same `operationId` defined in `openapi.yaml` whenever possible.
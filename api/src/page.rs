@@ -10,7 +10,7 @@ use serde::Deserialize;
 use std::num::NonZeroU16;
 
 const DEFAULT_PAGE_SIZE: u16 = 25;
-const MAX_PAGE_SIZE: u16 = 1000;
+pub(crate) const MAX_PAGE_SIZE: u16 = 1000;
 
 #[derive(Clone, Debug, Deserialize)]
 pub(crate) struct Page {
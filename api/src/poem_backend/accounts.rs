@@ -9,10 +9,10 @@ use std::sync::Arc;
 use super::accept_type::{parse_accept, AcceptType};
 use super::{
     ApiTags, AptosErrorResponse, BadRequestError, BasicResponse, BasicResponseStatus,
-    InternalError, NotFoundError,
+    InternalError, NotFoundError, RequestTimeoutError, ServiceUnavailableError,
 };
 use super::{AptosErrorCode, BasicErrorWith404, BasicResultWith404};
-use crate::context::Context;
+use crate::context::{Context, Overloaded, ReadTimeout, ResponseTooLarge};
 use crate::failpoint::fail_point_poem;
 use aptos_api_types::{AccountData, Address, AsConverter, MoveStructTag, TransactionId};
 use aptos_api_types::{LedgerInfo, MoveModuleBytecode, MoveResource};
@@ -80,7 +80,7 @@ impl AccountsApi {
         fail_point_poem("endpoint_get_account_resources")?;
         let accept_type = parse_accept(&accept)?;
         let account = Account::new(self.context.clone(), address.0, ledger_version.0)?;
-        account.resources(&accept_type)
+        account.resources(&accept_type).await
     }
 
     /// Get account modules
@@ -104,7 +104,7 @@ impl AccountsApi {
         fail_point_poem("endpoint_get_account_modules")?;
         let accept_type = parse_accept(&accept)?;
         let account = Account::new(self.context.clone(), address.0, ledger_version.0)?;
-        account.modules(&accept_type)
+        account.modules(&accept_type).await
     }
 }
 
@@ -171,8 +171,11 @@ impl Account {
         ))
     }
 
-    pub fn resources(self, accept_type: &AcceptType) -> BasicResultWith404<Vec<MoveResource>> {
-        let account_state = self.account_state()?;
+    pub async fn resources(
+        self,
+        accept_type: &AcceptType,
+    ) -> BasicResultWith404<Vec<MoveResource>> {
+        let account_state = self.account_state_with_timeout().await?;
         let resources = account_state.get_resources();
         let move_resolver = self.context.move_resolver_poem()?;
         let converted_resources = move_resolver
@@ -190,9 +193,12 @@ impl Account {
         ))
     }
 
-    pub fn modules(self, accept_type: &AcceptType) -> BasicResultWith404<Vec<MoveModuleBytecode>> {
+    pub async fn modules(
+        self,
+        accept_type: &AcceptType,
+    ) -> BasicResultWith404<Vec<MoveModuleBytecode>> {
         let mut modules = Vec::new();
-        for module in self.account_state()?.into_modules() {
+        for module in self.account_state_with_timeout().await?.into_modules() {
             modules.push(
                 MoveModuleBytecode::new(module)
                     .try_parse_abi()
@@ -214,9 +220,36 @@ impl Account {
     fn account_state(&self) -> Result<AccountState, BasicErrorWith404> {
         let state = self
             .context
-            .get_account_state(self.address.into(), self.ledger_version)
-            .map_err(BasicErrorWith404::internal)
-            .map_err(|e| e.error_code(AptosErrorCode::ReadFromStorageError))?
+            .get_account_state(self.address.into(), self.ledger_version);
+        self.account_state_or_error(state)
+    }
+
+    /// Same as `account_state`, with the scan bounded by `Context::read_with_timeout`
+    async fn account_state_with_timeout(&self) -> Result<AccountState, BasicErrorWith404> {
+        let state = self
+            .context
+            .get_state_values_with_timeout(self.address.into(), self.ledger_version)
+            .await
+            .and_then(|state_values| AccountState::from_access_paths_and_values(&state_values));
+        self.account_state_or_error(state)
+    }
+
+    fn account_state_or_error(
+        &self,
+        state: anyhow::Result<Option<AccountState>>,
+    ) -> Result<AccountState, BasicErrorWith404> {
+        let state = state
+            .map_err(|e| {
+                if e.is::<ResponseTooLarge>() {
+                    BasicErrorWith404::bad_request(e).error_code(AptosErrorCode::ResponseTooLarge)
+                } else if e.is::<ReadTimeout>() {
+                    BasicErrorWith404::request_timeout(e).error_code(AptosErrorCode::Timeout)
+                } else if e.is::<Overloaded>() {
+                    BasicErrorWith404::service_unavailable(e).error_code(AptosErrorCode::Overloaded)
+                } else {
+                    BasicErrorWith404::internal(e).error_code(AptosErrorCode::ReadFromStorageError)
+                }
+            })?
             .ok_or_else(|| self.account_not_found())?;
 
         Ok(state)
@@ -3,7 +3,7 @@
 
 use std::time::Duration;
 
-use crate::metrics::RESPONSE_STATUS;
+use crate::{log::RequestContext, metrics::RESPONSE_STATUS};
 use aptos_logger::{
     debug, error,
     prelude::{sample, SampleRate},
@@ -15,6 +15,8 @@ use poem::{http::header, Endpoint, Request, Response, Result};
 /// Logs information about the request and response if the response status code
 /// is >= 500, to help us debug since this will be an error on our side.
 /// We also do general logging of the status code alone regardless of what it is.
+/// The request is served as the `RequestContext` of its `X-Request-Id` header, which tags the
+/// slow query logs of its reads.
 pub async fn middleware_log<E: Endpoint>(next: E, request: Request) -> Result<Response> {
     let start = std::time::Instant::now();
 
@@ -38,7 +40,9 @@ pub async fn middleware_log<E: Endpoint>(next: E, request: Request) -> Result<Re
             .and_then(|v| v.to_str().ok().map(|v| v.to_string())),
     };
 
-    let response = next.get_response(request).await;
+    let response = RequestContext::from_headers(request.headers())
+        .scope(next.get_response(request))
+        .await;
 
     let elapsed = start.elapsed();
 
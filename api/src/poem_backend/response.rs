@@ -94,6 +94,59 @@ pub enum AptosErrorCode {
 
     /// The limit param given for paging is invalid.
     InvalidLimitParam = 5,
+
+    /// Mempool has been failing, so submissions are rejected without reaching
+    /// it until it has had time to recover.
+    MempoolUnavailable = 6,
+
+    /// The requested version is beyond the ledger version.
+    VersionNotFound = 7,
+
+    /// The requested version has been pruned.
+    VersionPruned = 8,
+
+    /// The node has no ledger info to serve requests against yet.
+    LedgerInfoNotFound = 9,
+
+    /// Serving the request took longer than the API allows for reads.
+    Timeout = 10,
+
+    /// The submitted transaction is larger than the API accepts.
+    PayloadTooLarge = 11,
+
+    /// Mempool is full and can't take the submitted transaction.
+    MempoolIsFull = 12,
+
+    /// The submitted transaction's sequence number was already used by the account.
+    SequenceNumberTooOld = 13,
+
+    /// The submitted transaction's sequence number is ahead of the account's.
+    SequenceNumberTooNew = 14,
+
+    /// The sender can't pay for the submitted transaction's max gas.
+    InsufficientBalanceForTransactionFee = 15,
+
+    /// The submitted transaction expired before it could be accepted.
+    TransactionExpired = 16,
+
+    /// The submitted transaction's signature doesn't verify.
+    InvalidTransactionSignature = 17,
+
+    /// The sender has as many transactions in mempool as it's allowed.
+    TooManyTransactionsForAccount = 18,
+
+    /// The submitted transaction was rejected for another reason.
+    TransactionRejected = 19,
+
+    /// The node is serving as many heavy reads as it allows, retry later.
+    Overloaded = 20,
+
+    /// The submitted bytes don't deserialize into a signed transaction.
+    InvalidTransactionEncoding = 21,
+
+    /// The response would hold more entries, or more bytes, than the node is configured to
+    /// return at once.
+    ResponseTooLarge = 22,
 }
 
 #[derive(ResponseContent)]
@@ -379,10 +432,13 @@ generate_success_response!(BasicResponse, (200, Ok));
 generate_error_traits!(
     BadRequest,
     NotFound,
+    Gone,
+    RequestTimeout,
     PayloadTooLarge,
     UnsupportedMediaType,
     Internal,
-    InsufficientStorage
+    InsufficientStorage,
+    ServiceUnavailable
 );
 
 // Generate an error response that only has options for 400 and 500.
@@ -391,12 +447,15 @@ generate_error_response!(BasicError, (400, BadRequest), (500, Internal));
 // This type just simplifies using BasicResponse and BasicError together.
 pub type BasicResult<T> = poem::Result<BasicResponse<T>, BasicError>;
 
-// As above but with 404.
+// As above but with 404, 408 for reads that ran past the configured read timeout, and 503 for
+// reads turned away while the node is overloaded.
 generate_error_response!(
     BasicErrorWith404,
     (400, BadRequest),
     (404, NotFound),
-    (500, Internal)
+    (408, RequestTimeout),
+    (500, Internal),
+    (503, ServiceUnavailable)
 );
 pub type BasicResultWith404<T> = poem::Result<BasicResponse<T>, BasicErrorWith404>;
 
@@ -10,10 +10,10 @@ use super::accept_type::{parse_accept, AcceptType};
 use super::page::Page;
 use super::AptosErrorCode;
 use super::{
-    ApiTags, AptosErrorResponse, BasicErrorWith404, BasicResponse, BasicResponseStatus,
-    BasicResultWith404, InternalError,
+    ApiTags, AptosErrorResponse, BadRequestError, BasicErrorWith404, BasicResponse,
+    BasicResponseStatus, BasicResultWith404, InternalError,
 };
-use crate::context::Context;
+use crate::context::{Context, ResponseSizeExceeded};
 use crate::failpoint::fail_point_poem;
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{AsConverter, LedgerInfo, Transaction, TransactionOnChainData};
@@ -45,12 +45,16 @@ impl TransactionsApi {
         fail_point_poem("endppoint_get_transactions")?;
         let accept_type = parse_accept(&accept)?;
         let page = Page::new(start.0, limit.0);
-        self.list(&accept_type, page)
+        self.list(&accept_type, page).await
     }
 }
 
 impl TransactionsApi {
-    fn list(&self, accept_type: &AcceptType, page: Page) -> BasicResultWith404<Vec<Transaction>> {
+    async fn list(
+        &self,
+        accept_type: &AcceptType,
+        page: Page,
+    ) -> BasicResultWith404<Vec<Transaction>> {
         let latest_ledger_info = self.context.get_latest_ledger_info_poem()?;
         let ledger_version = latest_ledger_info.version();
         let limit = page.limit()?;
@@ -60,13 +64,23 @@ impl TransactionsApi {
             0
         };
         let start_version = page.start(last_page_start, ledger_version)?;
+        let limit = self
+            .context
+            .limit_to_ledger(start_version, limit, ledger_version);
 
+        self.context
+            .check_transactions_response_size(start_version, limit, ledger_version)
+            .map_err(|e| {
+                if e.is::<ResponseSizeExceeded>() {
+                    BasicErrorWith404::bad_request(e).error_code(AptosErrorCode::ResponseTooLarge)
+                } else {
+                    BasicErrorWith404::internal(e).error_code(AptosErrorCode::ReadFromStorageError)
+                }
+            })?;
         let data = self
             .context
-            .get_transactions(start_version, limit, ledger_version)
-            .context("Failed to read raw transactions from storage")
-            .map_err(BasicErrorWith404::internal)
-            .map_err(|e| e.error_code(AptosErrorCode::InvalidBcsInStorageError))?;
+            .get_transactions_poem(start_version, limit, ledger_version)
+            .await?;
 
         self.render_transactions(data, accept_type, &latest_ledger_info)
     }
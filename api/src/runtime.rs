@@ -1,12 +1,20 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{context::Context, index, poem_backend::attach_poem_to_runtime};
+use crate::{
+    caching_db_reader::CachingDbReader, context::Context, index,
+    poem_backend::attach_poem_to_runtime,
+};
 use anyhow::Context as AnyhowContext;
 use aptos_config::config::{ApiConfig, NodeConfig};
+use aptos_logger::{
+    prelude::{sample, SampleRate},
+    sample::Sampling,
+    warn,
+};
 use aptos_mempool::MempoolClientSender;
 use aptos_types::chain_id::ChainId;
-use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+use std::{convert::Infallible, net::SocketAddr, sync::Arc, time::Duration};
 use storage_interface::DbReader;
 use tokio::runtime::{Builder, Runtime};
 use warp::{Filter, Reply};
@@ -28,12 +36,18 @@ pub fn bootstrap(
         .enable_all()
         .build()
         .context("[api] failed to create runtime")?;
+    let db: Arc<dyn DbReader> = match config.api.db_read_cache_capacity() {
+        0 => db,
+        capacity => Arc::new(CachingDbReader::new(db, capacity as usize)),
+    };
     let context = Context::new(chain_id, db, mp_sender, config.clone());
 
     // Poem will run on a different port.
     let poem_address = attach_poem_to_runtime(&runtime, context.clone(), config)
         .context("Failed to attach poem to runtime")?;
 
+    runtime.spawn(report_mempool_status(context.clone()));
+
     let api = WebServer::from(config.api.clone());
     runtime.spawn(async move {
         // TODO: This proxy is temporary while we have both APIs running.
@@ -48,6 +62,26 @@ pub fn bootstrap(
     Ok(runtime)
 }
 
+// Shortest interval between mempool status polls, so that a misconfigured interval of 0
+// doesn't make the poller spin
+const MIN_MEMPOOL_STATUS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Keeps the mempool queue gauges up to date for operator dashboards
+async fn report_mempool_status(context: Context) {
+    let interval = Duration::from_millis(context.mempool_status_poll_interval_ms())
+        .max(MIN_MEMPOOL_STATUS_POLL_INTERVAL);
+    loop {
+        if let Err(error) = context.get_mempool_status().await {
+            // Polled every interval, so an unreachable mempool would otherwise flood the logs
+            sample!(
+                SampleRate::Duration(Duration::from_secs(60)),
+                warn!("Failed to get mempool status: {:#}", error)
+            );
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct WebServer {
     pub address: SocketAddr,
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    context::Context,
+    context::{Context, RouteKind},
     failpoint::fail_point,
     metrics::metrics,
     param::{
@@ -62,7 +62,7 @@ pub fn get_table_item(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("tables" / TableHandleParam / "item")
         .and(warp::post())
         .and(warp::body::content_length_limit(
-            context.content_length_limit(),
+            context.content_length_limit_for(RouteKind::Read),
         ))
         .and(warp::body::json::<TableItemRequest>())
         .and(context.filter())
@@ -2,8 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    context::{ResponseTooLarge, Staleness},
     current_function_name,
-    tests::{find_value, new_test_context},
+    tests::{
+        context_with_db, context_with_db_and_config, find_value, new_test_context,
+        new_test_context_with_config, synthetic_account, StateValuesDb,
+    },
+};
+use aptos_api_types::U64;
+use aptos_config::config::NodeConfig;
+use aptos_types::{
+    account_address::AccountAddress, account_config::AccountResource,
+    transaction::authenticator::AuthenticationKey, utility_coin::APTOS_COIN_TYPE,
+};
+use move_deps::move_core_types::{
+    ident_str,
+    language_storage::{StructTag, TypeTag},
+    move_resource::MoveStructType,
 };
 use serde_json::json;
 
@@ -204,6 +219,357 @@ async fn test_get_core_account_data_not_found() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_account_transaction_count() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let root_address = context.root_account().address();
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    assert_eq!(
+        context
+            .context
+            .get_account_transaction_count(account.address(), ledger_version)
+            .unwrap(),
+        0
+    );
+    let root_count = context
+        .context
+        .get_account_transaction_count(root_address, ledger_version)
+        .unwrap();
+
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    assert_eq!(
+        context
+            .context
+            .get_account_transaction_count(root_address, ledger_version)
+            .unwrap(),
+        root_count + 1
+    );
+}
+
+#[tokio::test]
+async fn test_diff_account_state() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let before = context.get_latest_ledger_info().version();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let after = context.get_latest_ledger_info().version();
+
+    let diff = context
+        .context
+        .diff_account_state(account.address(), before, after)
+        .unwrap();
+    assert!(!diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+    assert!(diff.changed.is_empty());
+
+    assert!(context
+        .context
+        .diff_account_state(account.address(), after, before)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_decode_resource_field() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let account_resource = AccountResource::struct_tag();
+    let sequence_number = context
+        .context
+        .decode_resource_field::<U64>(
+            ledger_version,
+            account.address(),
+            &account_resource,
+            ident_str!("sequence_number"),
+        )
+        .unwrap();
+    assert_eq!(sequence_number.map(|n| n.0), Some(0));
+
+    let unknown = context.gen_account();
+    let missing = context
+        .context
+        .decode_resource_field::<U64>(
+            ledger_version,
+            unknown.address(),
+            &account_resource,
+            ident_str!("sequence_number"),
+        )
+        .unwrap();
+    assert!(missing.is_none());
+
+    assert!(context
+        .context
+        .decode_resource_field::<U64>(
+            ledger_version,
+            account.address(),
+            &account_resource,
+            ident_str!("no_such_field"),
+        )
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_get_coin_balance() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let mut root = context.root_account();
+    let create_txn = context.create_user_account_by(&mut root, &account);
+    let mint_txn = root
+        .sign_with_transaction_builder(context.transaction_factory().mint(account.address(), 1000));
+    context.commit_block(&vec![create_txn, mint_txn]).await;
+
+    let version = context.get_latest_ledger_info().version();
+    let aptos_coin = match APTOS_COIN_TYPE.clone() {
+        TypeTag::Struct(struct_tag) => struct_tag,
+        _ => unreachable!(),
+    };
+    assert_eq!(
+        context
+            .context
+            .get_coin_balance(account.address(), &aptos_coin, version)
+            .unwrap(),
+        1000
+    );
+
+    // An account without a `CoinStore` for the coin type has nothing of it
+    let other_coin = StructTag {
+        name: ident_str!("OtherCoin").to_owned(),
+        ..aptos_coin.clone()
+    };
+    assert_eq!(
+        context
+            .context
+            .get_coin_balance(account.address(), &other_coin, version)
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_get_address_for_auth_key() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let version = context.get_latest_ledger_info().version();
+
+    assert_eq!(
+        context
+            .context
+            .get_address_for_auth_key(account.authentication_key(), version)
+            .unwrap(),
+        Some(account.address())
+    );
+    assert_eq!(
+        context
+            .context
+            .get_address_for_auth_key(AuthenticationKey::random(), version)
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_get_account_state_bounded_staleness() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let latest_version = context.get_latest_ledger_info().version();
+
+    for max_staleness in [
+        Staleness::Versions(0),
+        Staleness::Versions(u64::MAX),
+        Staleness::Duration(std::time::Duration::from_secs(3600)),
+    ] {
+        let (version, state) = context
+            .context
+            .get_account_state_bounded_staleness(account.address(), max_staleness)
+            .unwrap();
+        assert!(version <= latest_version);
+        if max_staleness == Staleness::Versions(0) {
+            assert_eq!(version, latest_version);
+        }
+        // Blocks end with a state checkpoint, so the account is there either way
+        assert!(state.is_some());
+    }
+}
+
+#[tokio::test]
+async fn test_get_coin_types() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let version = context.get_latest_ledger_info().version();
+    let aptos_coin = match APTOS_COIN_TYPE.clone() {
+        TypeTag::Struct(struct_tag) => struct_tag,
+        _ => unreachable!(),
+    };
+    assert_eq!(
+        context
+            .context
+            .get_coin_types(account.address(), version)
+            .unwrap(),
+        vec![aptos_coin]
+    );
+
+    let no_account = context.gen_account();
+    assert!(context
+        .context
+        .get_coin_types(no_account.address(), version)
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_get_system_resources() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let resources = context.context.get_system_resources(version).unwrap();
+
+    let chain_id_tag = StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("chain_id").to_owned(),
+        name: ident_str!("ChainId").to_owned(),
+        type_params: vec![],
+    };
+    let chain_id: u8 = bcs::from_bytes(&resources[&chain_id_tag]).unwrap();
+    assert_eq!(chain_id, context.context.chain_id().id());
+    assert!(resources
+        .keys()
+        .any(|struct_tag| struct_tag.module.as_str() == "block"
+            && struct_tag.name.as_str() == "BlockMetadata"));
+}
+
+#[tokio::test]
+async fn test_needs_account_creation() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    assert!(context
+        .context
+        .needs_account_creation(account.address())
+        .unwrap());
+
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    // Twice, the second time from the cache
+    for _ in 0..2 {
+        assert!(!context
+            .context
+            .needs_account_creation(account.address())
+            .unwrap());
+    }
+    assert!(!context
+        .context
+        .needs_account_creation(context.root_account().address())
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_get_state_values_over_the_limit() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_account_state_values = Some(3);
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let version = context.get_latest_ledger_info().version();
+
+    // The framework account holds far more than a handful of resources and modules
+    let err = context
+        .context
+        .get_state_values(AccountAddress::ONE, version)
+        .unwrap_err();
+    assert!(err.is::<ResponseTooLarge>());
+    let resp = context
+        .expect_status_code(400)
+        .get("/accounts/0x1/resources")
+        .await;
+    assert!(resp["message"]
+        .as_str()
+        .unwrap()
+        .contains("more than the maximum of 3"));
+
+    // Accounts within the limit are still read whole
+    let account = context.gen_account();
+    assert!(context
+        .context
+        .get_state_values(account.address(), version)
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_get_resources_decoded() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let version = context.get_latest_ledger_info().version();
+
+    let aptos_coin = match APTOS_COIN_TYPE.clone() {
+        TypeTag::Struct(struct_tag) => struct_tag,
+        _ => unreachable!(),
+    };
+    let coin_store = StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("coin").to_owned(),
+        name: ident_str!("CoinStore").to_owned(),
+        type_params: vec![TypeTag::Struct(aptos_coin.clone())],
+    };
+    let missing = StructTag {
+        name: ident_str!("OtherCoin").to_owned(),
+        ..aptos_coin
+    };
+    let resources = context
+        .context
+        .get_resources_decoded(
+            account.address(),
+            &[AccountResource::struct_tag(), missing, coin_store],
+            version,
+        )
+        .unwrap();
+    assert_eq!(resources.len(), 3);
+    assert_eq!(
+        resources[0].as_ref().unwrap()["data"]["sequence_number"],
+        "0"
+    );
+    assert!(resources[1].is_none());
+    assert_eq!(resources[2].as_ref().unwrap()["data"]["coin"]["value"], "0");
+}
+
+#[tokio::test]
+async fn test_get_state_values_of_large_account() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let address = AccountAddress::random();
+    let values = synthetic_account(address, 12_000);
+
+    // Trips the default cap rather than the storage limit on whole prefix reads
+    let synthetic = context_with_db(StateValuesDb::over(context.db.clone(), values.clone()));
+    let err = synthetic.get_state_values(address, version).unwrap_err();
+    let too_large = err.downcast_ref::<ResponseTooLarge>().unwrap();
+    assert_eq!(too_large.limit, 10_000);
+
+    // A cap above the storage limit lets the whole account be read
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_account_state_values = Some(20_000);
+    let synthetic =
+        context_with_db_and_config(StateValuesDb::over(context.db.clone(), values), node_config);
+    assert_eq!(
+        synthetic.get_state_values(address, version).unwrap().len(),
+        12_000
+    );
+}
+
 fn account_resources(address: &str) -> String {
     format!("/accounts/{}/resources", address)
 }
@@ -1,7 +1,20 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{current_function_name, tests::new_test_context};
+use crate::{
+    current_function_name,
+    tests::{context_with_db, new_test_context, new_test_context_with_config, StateValuesDb},
+};
+use aptos_config::config::NodeConfig;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::AccountResource,
+    event::{EventHandle, EventKey},
+    on_chain_config::new_epoch_event_key,
+    state_store::{state_key::StateKey, state_value::StateValue},
+};
+use move_deps::move_core_types::{language_storage::ResourceKey, move_resource::MoveStructType};
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 
 static EVENT_KEY: &str =
@@ -130,3 +143,282 @@ async fn test_get_events_by_struct_type_has_generic_type_parameter() {
     let resp = context.expect_status_code(404).get(path.as_str()).await;
     context.check_golden_output(resp);
 }
+
+#[tokio::test]
+async fn test_get_event_count_of_unknown_handle() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let event_key = EventKey::new(
+        u64::MAX,
+        AccountAddress::from_hex_literal("0xA550C18").unwrap(),
+    );
+
+    assert_eq!(
+        context
+            .context
+            .get_event_count(&event_key, ledger_version)
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_get_latest_events() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let event_key = EventKey::new(5, AccountAddress::from_hex_literal("0xA550C18").unwrap());
+    let count = context
+        .context
+        .get_event_count(&event_key, ledger_version)
+        .unwrap();
+    assert!(count > 0);
+
+    let events = context
+        .context
+        .get_latest_events(&event_key, 100, ledger_version)
+        .unwrap();
+    assert_eq!(events.len() as u64, count);
+    let sequence_numbers: Vec<_> = events.iter().map(|e| e.sequence_number()).collect();
+    let expected: Vec<_> = (0..count).rev().collect();
+    assert_eq!(sequence_numbers, expected);
+
+    let newest = context
+        .context
+        .get_latest_events(&event_key, 1, ledger_version)
+        .unwrap();
+    assert_eq!(newest, events[..1].to_vec());
+}
+
+#[tokio::test]
+async fn test_get_events_with_txn_hash() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let event_key = EventKey::new(5, AccountAddress::from_hex_literal("0xA550C18").unwrap());
+
+    let events = context
+        .context
+        .get_events_with_txn_hash(&event_key, 0, 100, ledger_version)
+        .unwrap();
+    assert!(!events.is_empty());
+    for (event, txn_hash) in events {
+        let txn = context
+            .context
+            .get_transaction_by_hash(txn_hash, ledger_version)
+            .unwrap()
+            .unwrap();
+        assert!(txn.events.contains(&event));
+    }
+}
+
+#[tokio::test]
+async fn test_get_events_in_range() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let limit = context.context.limit_to_ledger(0, 10, ledger_version);
+    let txns = context
+        .context
+        .get_transactions(0, limit, ledger_version)
+        .unwrap();
+    let events = context
+        .context
+        .get_events_in_range(0, 10, ledger_version)
+        .unwrap();
+
+    assert_eq!(events.len(), txns.len());
+    for ((version, events), txn) in events.into_iter().zip(txns) {
+        assert_eq!(version, txn.version);
+        assert_eq!(events, txn.events);
+    }
+}
+
+#[tokio::test]
+async fn test_events_limit_clamped_to_configured_max() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_events_page_size = Some(1);
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+    let ledger_version = context.get_latest_ledger_info().version();
+    let event_key = EventKey::new(5, AccountAddress::from_hex_literal("0xA550C18").unwrap());
+
+    let events = context
+        .context
+        .get_events(&event_key, 0, 100, ledger_version)
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    let events = context
+        .context
+        .get_latest_events(&event_key, 100, ledger_version)
+        .unwrap();
+    assert_eq!(events.len(), 1);
+}
+
+#[tokio::test]
+async fn test_get_events_merged() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let event_keys = vec![
+        EventKey::new(5, AccountAddress::from_hex_literal("0xA550C18").unwrap()),
+        new_epoch_event_key(),
+    ];
+
+    let mut expected_len = 0;
+    let merged = context
+        .context
+        .get_events_merged(&event_keys, 1000, ledger_version)
+        .unwrap();
+    for event_key in &event_keys {
+        let events = context
+            .context
+            .get_events(event_key, 0, 1000, ledger_version)
+            .unwrap();
+        assert!(!events.is_empty());
+        expected_len += events.len();
+        // Each handle's events keep their order within the merge
+        let from_handle: Vec<_> = merged
+            .iter()
+            .filter(|event| event.key() == event_key)
+            .cloned()
+            .collect();
+        assert_eq!(from_handle, events);
+    }
+    assert_eq!(merged.len(), expected_len);
+
+    let truncated = context
+        .context
+        .get_events_merged(&event_keys, 1, ledger_version)
+        .unwrap();
+    assert_eq!(truncated, merged[..1].to_vec());
+}
+
+#[tokio::test]
+async fn test_derive_event_key() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let address = AccountAddress::from_hex_literal("0xA550C18").unwrap();
+
+    let event_key = crate::context::derive_event_key(5, address);
+    // Matches the key the events routes parse
+    let parsed: aptos_api_types::EventKey = EVENT_KEY.parse().unwrap();
+    assert_eq!(event_key, EventKey::from(parsed));
+    let events = context
+        .context
+        .get_events(&event_key, 0, 100, ledger_version)
+        .unwrap();
+    assert!(!events.is_empty());
+    assert!(events.iter().all(|event| *event.key() == event_key));
+}
+
+#[tokio::test]
+async fn test_get_account_events() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let address = AccountAddress::from_hex_literal("0xA550C18").unwrap();
+
+    let events = context
+        .context
+        .get_account_events(address, 5, 0, 100, ledger_version)
+        .unwrap();
+    let expected = context
+        .context
+        .get_events(&EventKey::new(5, address), 0, 100, ledger_version)
+        .unwrap();
+    assert!(!events.is_empty());
+    assert_eq!(events, expected);
+}
+
+#[tokio::test]
+async fn test_get_account_total_event_count() {
+    let mut context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let address = AccountAddress::from_hex_literal("0xA550C18").unwrap();
+
+    let total = context
+        .context
+        .get_account_total_event_count(address, ledger_version)
+        .unwrap();
+    let count = context
+        .context
+        .get_event_count(&EventKey::new(5, address), ledger_version)
+        .unwrap();
+    assert!(count > 0);
+    assert!(total >= count);
+
+    let no_account = context.gen_account();
+    assert_eq!(
+        context
+            .context
+            .get_account_total_event_count(no_account.address(), ledger_version)
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_get_events_decoded() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let event_key = EventKey::new(5, AccountAddress::from_hex_literal("0xA550C18").unwrap());
+
+    let events = context
+        .context
+        .get_events(&event_key, 0, 10, ledger_version)
+        .unwrap();
+    let decoded = context
+        .context
+        .get_events_decoded(&event_key, 0, 10, ledger_version)
+        .unwrap();
+    assert!(!decoded.is_empty());
+    assert_eq!(decoded.len(), events.len());
+    for (event, decoded) in events.iter().zip(&decoded) {
+        assert!(decoded.is_decoded());
+        assert_eq!(decoded.sequence_number, event.sequence_number());
+        assert_eq!(decoded.bytes, event.event_data());
+    }
+}
+
+#[tokio::test]
+async fn test_event_count_ignores_fields_shaped_like_handles() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let address = AccountAddress::random();
+
+    // A 47 byte authentication key serializes to the 48 bytes of an event handle, one with a
+    // counter of 47 and the key (1000, address)
+    let mut authentication_key = vec![0u8; 7];
+    authentication_key.extend(1000u64.to_le_bytes());
+    authentication_key.extend(address.to_vec());
+    let account = AccountResource::new(
+        0,
+        authentication_key,
+        address,
+        EventHandle::new(EventKey::new(0, address), 3),
+    );
+    let context = context_with_db(StateValuesDb::over(
+        context.db.clone(),
+        [(
+            StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+                address,
+                AccountResource::struct_tag(),
+            ))),
+            StateValue::from(bcs::to_bytes(&account).unwrap()),
+        )],
+    ));
+
+    assert_eq!(
+        context
+            .get_event_count(&EventKey::new(1000, address), ledger_version)
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        context
+            .get_event_count(&EventKey::new(0, address), ledger_version)
+            .unwrap(),
+        3
+    );
+    assert_eq!(
+        context
+            .get_account_total_event_count(address, ledger_version)
+            .unwrap(),
+        3
+    );
+}
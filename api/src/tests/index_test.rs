@@ -1,8 +1,32 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{current_function_name, tests::new_test_context};
+use crate::{
+    context::{Context, VersionUnavailable},
+    current_function_name,
+    log::{RequestContext, REQUEST_ID_HEADER},
+    poem_backend::{middleware_log, BasicError},
+    tests::{
+        context_with_db, context_with_db_and_config, new_test_context, new_test_context_with_config,
+    },
+};
+use anyhow::{anyhow, Result};
+use aptos_config::config::NodeConfig;
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_types::{
+    block_info::BlockInfo,
+    chain_id::ChainId,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+};
+use futures::channel::mpsc;
+use poem::{endpoint::make_sync, Request};
 use serde_json::json;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+};
+use storage_interface::DbReader;
 
 #[tokio::test]
 async fn test_get_index() {
@@ -114,3 +138,311 @@ async fn test_cors_on_non_200_responses() {
     let cors_header = resp.headers().get("access-control-allow-origin").unwrap();
     assert_eq!(cors_header, "*");
 }
+
+#[tokio::test]
+async fn test_get_epoch_and_round() {
+    let mut context = new_test_context(current_function_name!());
+    assert_eq!(context.context.get_epoch_and_round().unwrap(), (0, 0));
+
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    // Blocks committed by the test context are all round 1 of the genesis epoch
+    assert_eq!(context.context.get_epoch_and_round().unwrap(), (0, 1));
+}
+
+#[tokio::test]
+async fn test_get_validator_set() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let validator_set = context.context.get_validator_set(version).unwrap();
+    // The test genesis has a single validator
+    assert_eq!(validator_set.payload().count(), 1);
+    // Served from the per epoch cache
+    assert_eq!(
+        context.context.get_validator_set(version).unwrap(),
+        validator_set
+    );
+}
+
+#[tokio::test]
+async fn test_health_check_with_mempool_probe() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.health_check_mempool_probe = Some(true);
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+    let resp = context
+        .reply(warp::test::request().method("GET").path("/-/healthy"))
+        .await;
+    assert_eq!(resp.status(), 200)
+}
+
+#[test]
+fn test_get_latest_ledger_info_poem_error_codes() {
+    let context = context_with_db(UnreadableLedgerInfoDb {
+        first_txn_version: Some(0),
+    });
+    let error = context
+        .get_latest_ledger_info_poem::<BasicError>()
+        .unwrap_err();
+    assert!(format!("{:?}", error).contains("error_code: Some(ReadFromStorageError)"));
+
+    let context = context_with_db(UnreadableLedgerInfoDb {
+        first_txn_version: None,
+    });
+    let error = context
+        .get_latest_ledger_info_poem::<BasicError>()
+        .unwrap_err();
+    assert!(format!("{:?}", error).contains("error_code: Some(LedgerInfoNotFound)"));
+}
+
+// Serves the oldest version, but fails reading the latest ledger info
+struct UnreadableLedgerInfoDb {
+    first_txn_version: Option<u64>,
+}
+
+impl DbReader for UnreadableLedgerInfoDb {
+    fn get_first_txn_version(&self) -> Result<Option<u64>> {
+        Ok(self.first_txn_version)
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        Err(anyhow!("ledger info unreadable"))
+    }
+}
+
+#[test]
+fn test_ledger_info_cache_refreshes_on_new_epoch() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.ledger_info_cache_ttl_ms = Some(3_600_000);
+    let latest = Arc::new(Mutex::new(mock_ledger_info(1, 10)));
+    let context = context_with_db_and_config(
+        RolloverDb {
+            latest: latest.clone(),
+        },
+        node_config,
+    );
+    let ledger_info = context.get_latest_ledger_info().unwrap();
+    assert_eq!((ledger_info.epoch, ledger_info.version()), (1, 10));
+
+    // Within the epoch the cached ledger info is served for the whole TTL
+    *latest.lock() = mock_ledger_info(1, 11);
+    assert_eq!(context.get_latest_ledger_info().unwrap(), ledger_info);
+
+    // But not once the epoch rolled over
+    *latest.lock() = mock_ledger_info(2, 12);
+    let ledger_info = context.get_latest_ledger_info().unwrap();
+    assert_eq!((ledger_info.epoch, ledger_info.version()), (2, 12));
+}
+
+// Serves whatever ledger info the test sets as the latest one
+struct RolloverDb {
+    latest: Arc<Mutex<LedgerInfoWithSignatures>>,
+}
+
+impl DbReader for RolloverDb {
+    fn get_first_txn_version(&self) -> Result<Option<u64>> {
+        Ok(Some(0))
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        Ok(Some(self.latest.lock().clone()))
+    }
+}
+
+#[test]
+fn test_latest_ledger_info_retried_during_reconfiguration() {
+    // The read fails once while the new epoch is being committed
+    let context = context_with_db(TransitionDb {
+        reads: Mutex::new(vec![None, Some(mock_ledger_info(2, 12))].into()),
+    });
+    let ledger_info = context.get_latest_ledger_info().unwrap();
+    assert_eq!((ledger_info.epoch, ledger_info.version()), (2, 12));
+
+    // But is only retried once
+    let context = context_with_db(TransitionDb {
+        reads: Mutex::new(vec![None, None, Some(mock_ledger_info(2, 12))].into()),
+    });
+    assert!(context.get_latest_ledger_info().is_err());
+}
+
+// Serves the ledger infos of `reads` in order, repeating the last one, with `None` failing the
+// read
+struct TransitionDb {
+    reads: Mutex<VecDeque<Option<LedgerInfoWithSignatures>>>,
+}
+
+impl DbReader for TransitionDb {
+    fn get_first_txn_version(&self) -> Result<Option<u64>> {
+        Ok(Some(0))
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        let mut reads = self.reads.lock();
+        let read = if reads.len() > 1 {
+            reads.pop_front().unwrap()
+        } else {
+            reads[0].clone()
+        };
+        read.map(Some)
+            .ok_or_else(|| anyhow!("ledger info unavailable during reconfiguration"))
+    }
+}
+
+#[tokio::test]
+async fn test_get_liveness_info() {
+    let mut context = new_test_context(current_function_name!());
+    context.commit_block(&[]).await;
+
+    let liveness = context.context.get_liveness_info().unwrap();
+    let ledger_info = context.get_latest_ledger_info();
+    assert_eq!(liveness.ledger_version, ledger_info.version());
+    assert_eq!(liveness.block_timestamp_usecs, ledger_info.timestamp());
+}
+
+fn mock_ledger_info(epoch: u64, version: u64) -> LedgerInfoWithSignatures {
+    let block_info = BlockInfo::new(
+        epoch,
+        0,
+        HashValue::zero(),
+        HashValue::zero(),
+        version,
+        0,
+        None,
+    );
+    LedgerInfoWithSignatures::new(
+        LedgerInfo::new(block_info, HashValue::zero()),
+        BTreeMap::new(),
+    )
+}
+
+#[tokio::test]
+async fn test_get_mempool_status() {
+    let context = new_test_context(current_function_name!());
+    let status = context.context.get_mempool_status().await.unwrap();
+    assert_eq!(status.size, 0);
+    assert!(status.capacity > 0);
+}
+
+#[tokio::test]
+async fn test_get_mempool_status_times_out() {
+    let test_context = new_test_context(current_function_name!());
+    let mut node_config = NodeConfig::default();
+    node_config.api.health_check_mempool_timeout_ms = Some(100);
+    // Mempool takes the request but never answers
+    let (mp_sender, _mp_receiver) = mpsc::channel(1);
+    let context = Context::new(
+        ChainId::test(),
+        test_context.db.clone(),
+        mp_sender,
+        node_config,
+    );
+
+    let err = context.get_mempool_status().await.unwrap_err();
+    assert!(err.to_string().contains("didn't respond within 100ms"));
+}
+
+#[tokio::test]
+async fn test_get_ledger_info_with_signatures_at_version() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let latest = context
+        .context
+        .get_latest_ledger_info_with_signatures()
+        .unwrap();
+    let latest_version = latest.ledger_info().version();
+
+    for version in [0, latest_version] {
+        let ledger_info = context
+            .context
+            .get_ledger_info_with_signatures_at_version(version)
+            .unwrap();
+        assert!(ledger_info.ledger_info().version() >= version);
+        assert_eq!(
+            ledger_info.ledger_info().version(),
+            context
+                .context
+                .get_ledger_info_at_version(version)
+                .unwrap()
+                .version()
+        );
+    }
+    assert!(context
+        .context
+        .get_ledger_info_with_signatures_at_version(latest_version + 1)
+        .unwrap_err()
+        .is::<VersionUnavailable>());
+}
+
+#[tokio::test]
+async fn test_node_info() {
+    let context = new_test_context(current_function_name!());
+    let node_info = context.context.node_info();
+    assert_eq!(node_info.role, context.context.node_role());
+    assert_eq!(node_info.is_validator, node_info.role.is_validator());
+    assert_eq!(node_info.chain_id, context.context.chain_id());
+    assert!(!node_info.api_version.is_empty());
+}
+
+#[tokio::test]
+async fn test_refresh_ledger_info_cache() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.ledger_info_cache_ttl_ms = Some(3_600_000);
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let cached = context.context.get_latest_ledger_info().unwrap();
+
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    // Served from the cache until it's refreshed
+    assert_eq!(context.context.get_latest_ledger_info().unwrap(), cached);
+    context.context.refresh_ledger_info_cache().unwrap();
+    let refreshed = context.context.get_latest_ledger_info().unwrap();
+    assert!(refreshed.version() > cached.version());
+    assert_eq!(
+        refreshed.version(),
+        context.context.db.get_latest_version().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_request_context_follows_reads() {
+    let context = new_test_context(current_function_name!());
+    assert_eq!(RequestContext::current(), RequestContext::default());
+
+    let request = RequestContext::new("trace-1");
+    let expected = request.clone();
+    let current = request
+        .scope(async move {
+            assert_eq!(RequestContext::current(), expected);
+            // Still current once resumed, wherever that is
+            tokio::task::yield_now().await;
+            assert_eq!(RequestContext::current(), expected);
+            // Carried over to the blocking pool
+            context
+                .context
+                .read_with_timeout(|_| Ok(RequestContext::current()))
+                .await
+                .unwrap()
+        })
+        .await;
+    assert_eq!(current, RequestContext::new("trace-1"));
+    assert_eq!(RequestContext::current(), RequestContext::default());
+}
+
+#[tokio::test]
+async fn test_request_context_from_request_id_header() {
+    let endpoint = make_sync(|_| RequestContext::current().trace_id.unwrap_or_default());
+
+    let request = Request::builder()
+        .header(REQUEST_ID_HEADER, "trace-2")
+        .finish();
+    let response = middleware_log(&endpoint, request).await.unwrap();
+    assert_eq!(response.into_body().into_string().await.unwrap(), "trace-2");
+
+    let response = middleware_log(&endpoint, Request::default()).await.unwrap();
+    assert_eq!(response.into_body().into_string().await.unwrap(), "");
+    assert_eq!(RequestContext::current(), RequestContext::default());
+}
@@ -13,8 +13,169 @@ mod test_context;
 mod transaction_vector_test;
 mod transactions_test;
 
+use crate::context::Context;
+use anyhow::{anyhow, Result};
+use aptos_config::config::NodeConfig;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    ledger_info::LedgerInfoWithSignatures,
+    state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix, state_value::StateValue},
+    transaction::Version,
+};
+use futures::channel::mpsc;
+use move_deps::move_core_types::{
+    identifier::Identifier,
+    language_storage::{ResourceKey, StructTag},
+};
 use serde_json::Value;
-pub use test_context::{new_test_context, TestContext};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+use storage_interface::DbReader;
+pub use test_context::{new_test_context, new_test_context_with_config, TestContext};
+
+/// Creates a context reading from `db` instead of a database with a genesis, for tests that
+/// need state no transaction can produce
+pub fn context_with_db(db: impl DbReader + 'static) -> Context {
+    context_with_db_and_config(db, NodeConfig::default())
+}
+
+pub fn context_with_db_and_config(db: impl DbReader + 'static, node_config: NodeConfig) -> Context {
+    let (mp_sender, _) = mpsc::channel(1);
+    Context::new(ChainId::test(), Arc::new(db), mp_sender, node_config)
+}
+
+/// Serves a fixed set of state values, at every version, with the prefix reads of `AptosDB`:
+/// keys in storage order and unbounded scans failing past `max_prefix_values`. Layered over
+/// another database with `StateValuesDb::over`, the values take precedence over its state and
+/// everything else is read from it.
+pub struct StateValuesDb {
+    inner: Option<Arc<dyn DbReader>>,
+    values: BTreeMap<Vec<u8>, (StateKey, StateValue)>,
+    max_prefix_values: usize,
+}
+
+impl StateValuesDb {
+    pub fn new(values: impl IntoIterator<Item = (StateKey, StateValue)>) -> Self {
+        Self {
+            inner: None,
+            values: values
+                .into_iter()
+                .map(|(key, value)| (key.encode().unwrap(), (key, value)))
+                .collect(),
+            max_prefix_values: 10_000,
+        }
+    }
+
+    pub fn over(
+        inner: Arc<dyn DbReader>,
+        values: impl IntoIterator<Item = (StateKey, StateValue)>,
+    ) -> Self {
+        Self {
+            inner: Some(inner),
+            ..Self::new(values)
+        }
+    }
+
+    fn inner(&self) -> Result<&dyn DbReader> {
+        self.inner
+            .as_deref()
+            .ok_or_else(|| anyhow!("No database under the state values"))
+    }
+
+    fn scan<'a>(
+        &'a self,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+    ) -> impl Iterator<Item = (Vec<u8>, (StateKey, StateValue))> + 'a {
+        let prefix = key_prefix.encode().unwrap();
+        let start = start_key.map_or_else(|| prefix.clone(), |key| key.encode().unwrap());
+        self.values
+            .range(start..)
+            .take_while(move |(key, _)| key.starts_with(&prefix))
+            .map(|(key, entry)| (key.clone(), entry.clone()))
+    }
+}
+
+impl DbReader for StateValuesDb {
+    fn get_first_txn_version(&self) -> Result<Option<Version>> {
+        self.inner()?.get_first_txn_version()
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        self.inner()?.get_latest_ledger_info_option()
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        match self.values.get(&state_key.encode()?) {
+            Some((_, value)) => Ok(Some(value.clone())),
+            None => self.inner()?.get_state_value_by_version(state_key, version),
+        }
+    }
+
+    fn get_state_values_by_key_prefix(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        version: Version,
+    ) -> Result<HashMap<StateKey, StateValue>> {
+        let mut values = match &self.inner {
+            Some(inner) => inner.get_state_values_by_key_prefix(key_prefix, version)?,
+            None => HashMap::new(),
+        };
+        values.extend(self.scan(key_prefix, None).map(|(_, entry)| entry));
+        if values.len() > self.max_prefix_values {
+            return Err(anyhow!("Too many values requested for {:?}", key_prefix));
+        }
+        Ok(values)
+    }
+
+    fn get_state_values_by_key_prefix_from(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        version: Version,
+        limit: usize,
+    ) -> Result<Vec<(StateKey, StateValue)>> {
+        let mut values = BTreeMap::new();
+        if let Some(inner) = &self.inner {
+            for (key, value) in
+                inner.get_state_values_by_key_prefix_from(key_prefix, start_key, version, limit)?
+            {
+                values.insert(key.encode()?, (key, value));
+            }
+        }
+        values.extend(self.scan(key_prefix, start_key).take(limit));
+        Ok(values.into_values().take(limit).collect())
+    }
+}
+
+/// Returns the state of an account holding `count` resources, each of a distinct made up type,
+/// to layer over a test database with `StateValuesDb::over`. The values aren't valid resources.
+pub fn synthetic_account(address: AccountAddress, count: usize) -> Vec<(StateKey, StateValue)> {
+    (0..count)
+        .map(|i| {
+            let struct_tag = StructTag {
+                address,
+                module: Identifier::new(format!("module_{}", i)).unwrap(),
+                name: Identifier::new("Resource").unwrap(),
+                type_params: vec![],
+            };
+            (
+                StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+                    address, struct_tag,
+                ))),
+                StateValue::from(i.to_le_bytes().to_vec()),
+            )
+        })
+        .collect()
+}
 
 pub fn find_value(val: &Value, filter: for<'r> fn(&'r &Value) -> bool) -> Value {
     let resources = val
@@ -2,16 +2,34 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    context::ResponseTooLarge,
     current_function_name,
-    tests::{new_test_context, TestContext},
+    tests::{
+        context_with_db, context_with_db_and_config, new_test_context, synthetic_account,
+        StateValuesDb, TestContext,
+    },
 };
+use aptos_api_types::U64;
+use aptos_config::config::NodeConfig;
 use aptos_sdk::move_types::parser::parse_type_tag;
 use aptos_sdk::types::LocalAccount;
-use aptos_types::state_store::table::TableHandle;
-use move_deps::{move_core_types::account_address::AccountAddress, move_package::BuildConfig};
+use aptos_types::{
+    access_path::AccessPath,
+    account_config::AccountResource,
+    state_store::{state_key::StateKey, state_value::StateValue, table::TableHandle},
+};
+use move_deps::{
+    move_core_types::{
+        account_address::AccountAddress,
+        ident_str,
+        language_storage::{ResourceKey, StructTag, TypeTag},
+        move_resource::MoveStructType,
+    },
+    move_package::BuildConfig,
+};
 use serde::Serialize;
 use serde_json::{json, Value};
-use std::{convert::TryInto, path::PathBuf};
+use std::{collections::BTreeMap, convert::TryInto, path::PathBuf};
 use storage_interface::DbReader;
 
 #[tokio::test]
@@ -183,6 +201,459 @@ async fn test_get_table_item() {
     assert_table_item(ctx, &nested_table, "u8", "u8", 2, 3).await;
 }
 
+#[tokio::test]
+async fn test_get_state_values_parallel_matches_serial() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let large_account = AccountAddress::random();
+    let synthetic = context_with_db(StateValuesDb::over(
+        context.db.clone(),
+        synthetic_account(large_account, 5000),
+    ));
+    for address in [
+        AccountAddress::ONE,
+        AccountAddress::from_hex_literal("0xA550C18").unwrap(),
+        large_account,
+    ] {
+        let serial = synthetic.get_state_values(address, version).unwrap();
+        let parallel = synthetic
+            .get_state_values_parallel(address, version)
+            .unwrap();
+        assert!(!serial.is_empty());
+        assert_eq!(serial, parallel);
+    }
+    assert_eq!(
+        synthetic
+            .get_state_values_parallel(large_account, version)
+            .unwrap()
+            .len(),
+        5000
+    );
+    assert!(synthetic
+        .get_state_values_parallel(AccountAddress::random(), version)
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_get_state_values_parallel_capped() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_account_state_values = Some(1000);
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let address = AccountAddress::random();
+    let synthetic = context_with_db_and_config(
+        StateValuesDb::over(context.db.clone(), synthetic_account(address, 5000)),
+        node_config,
+    );
+
+    let err = synthetic
+        .get_state_values_parallel(address, version)
+        .unwrap_err();
+    assert_eq!(err.downcast_ref::<ResponseTooLarge>().unwrap().limit, 1000);
+}
+
+#[tokio::test]
+async fn test_get_state_values_parallel_near_storage_cap() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let address = AccountAddress::random();
+    // Just under the storage cap on unbounded prefix reads
+    let synthetic = context_with_db(StateValuesDb::over(
+        context.db.clone(),
+        synthetic_account(address, 9000),
+    ));
+
+    let serial = synthetic.get_state_values(address, version).unwrap();
+    let parallel = synthetic
+        .get_state_values_parallel(address, version)
+        .unwrap();
+    assert_eq!(serial.len(), 9000);
+    assert_eq!(serial, parallel);
+}
+
+#[tokio::test]
+async fn test_warm_state_cache() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let values = context
+        .context
+        .get_state_values(AccountAddress::ONE, version)
+        .unwrap();
+    let keys: Vec<_> = values.keys().cloned().collect();
+    assert!(!keys.is_empty());
+
+    context.context.warm_state_cache(version, &keys).unwrap();
+    context.context.warm_state_cache(version, &[]).unwrap();
+    // Warming only prefetches, reads afterwards see the same values
+    for key in &keys {
+        assert_eq!(
+            context.context.get_state_value(key, version).unwrap(),
+            values.get(key).and_then(|value| value.maybe_bytes.clone())
+        );
+    }
+    // Keys that don't exist don't fail the warming
+    context
+        .context
+        .warm_state_cache(version, &[StateKey::Raw(b"missing".to_vec())])
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_get_state_values_batch() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let mut keys: Vec<_> = context
+        .context
+        .get_state_values(AccountAddress::ONE, version)
+        .unwrap()
+        .into_keys()
+        .take(5)
+        .collect();
+    keys.push(StateKey::Raw(b"missing".to_vec()));
+
+    let values = context
+        .context
+        .get_state_values_batch(&keys, version)
+        .unwrap();
+    assert_eq!(values.len(), keys.len());
+    for (key, value) in keys.iter().zip(values) {
+        assert_eq!(
+            value,
+            context.context.get_state_value(key, version).unwrap()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_get_module_abi() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let abi = context
+        .context
+        .get_module_abi(AccountAddress::ONE, "guid", version)
+        .unwrap();
+    assert_eq!(abi.name.to_string(), "guid");
+    assert!(!abi.exposed_functions.is_empty());
+
+    assert!(context
+        .context
+        .get_module_abi(AccountAddress::ONE, "NoNoNo", version)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_export_account_snapshot() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let snapshot = context
+        .context
+        .export_account_snapshot(AccountAddress::ONE, version)
+        .unwrap();
+
+    assert_eq!(
+        snapshot.len(),
+        context
+            .context
+            .get_state_values(AccountAddress::ONE, version)
+            .unwrap()
+            .len()
+    );
+    assert!(snapshot.windows(2).all(|pair| pair[0].0 < pair[1].0));
+}
+
+#[tokio::test]
+async fn test_get_state_values_with_metadata() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let version = context.get_latest_ledger_info().version();
+
+    let values = context
+        .context
+        .get_state_values(account.address(), version)
+        .unwrap();
+    let with_metadata = context
+        .context
+        .get_state_values_with_metadata(account.address(), version)
+        .unwrap();
+    assert!(!with_metadata.is_empty());
+    assert_eq!(with_metadata.len(), values.len());
+    for (key, (value, metadata)) in with_metadata {
+        assert_eq!(values[&key], value);
+        // The account was created in the last block, so everything was written in it
+        assert!(metadata.last_modified_version > 0);
+        assert!(metadata.last_modified_version <= version);
+        // Same version a point lookup of the key finds
+        let (last_modified_version, _) = context
+            .context
+            .db
+            .get_state_value_with_version_by_version(&key, version)
+            .unwrap()
+            .unwrap();
+        assert_eq!(metadata.last_modified_version, last_modified_version);
+    }
+}
+
+#[tokio::test]
+async fn test_shared_converter_matches_converter_per_field() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let version = context.get_latest_ledger_info().version();
+    let account_resource = AccountResource::struct_tag();
+    let field = ident_str!("sequence_number");
+    let addresses = [
+        AccountAddress::ONE,
+        AccountAddress::from_hex_literal("0xA550C18").unwrap(),
+        account.address(),
+        AccountAddress::random(),
+    ];
+
+    let per_field: Vec<Option<U64>> = addresses
+        .iter()
+        .map(|address| {
+            context
+                .context
+                .decode_resource_field(version, *address, &account_resource, field)
+                .unwrap()
+        })
+        .collect();
+    let shared: Vec<Option<U64>> = context
+        .context
+        .with_converter(version, |converter| {
+            addresses
+                .iter()
+                .map(|address| {
+                    context.context.decode_resource_field_with(
+                        converter,
+                        version,
+                        *address,
+                        &account_resource,
+                        field,
+                    )
+                })
+                .collect()
+        })
+        .unwrap();
+    assert_eq!(shared, per_field);
+    // The root account has sent transactions, the random one doesn't exist
+    assert!(per_field[1].unwrap().0 > 0);
+    assert_eq!(per_field[2], Some(U64(0)));
+    assert_eq!(per_field[3], None);
+}
+
+#[tokio::test]
+async fn test_get_state_value_version() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let mut root = context.root_account();
+    let txn = context.create_user_account_by(&mut root, &account);
+    context.commit_block(&vec![txn]).await;
+    let created_version = context.get_latest_ledger_info().version();
+    let keys: Vec<_> = context
+        .context
+        .get_state_values(account.address(), created_version)
+        .unwrap()
+        .into_keys()
+        .collect();
+    assert!(!keys.is_empty());
+
+    // Writes to other accounts leave the account's state untouched
+    let other = context.gen_account();
+    let txn = context.create_user_account_by(&mut root, &other);
+    context.commit_block(&vec![txn]).await;
+    let version = context.get_latest_ledger_info().version();
+    assert!(version > created_version);
+
+    for key in &keys {
+        let last_modified = context
+            .context
+            .get_state_value_version(key, version)
+            .unwrap()
+            .unwrap();
+        assert!(last_modified <= created_version);
+    }
+    assert_eq!(
+        context
+            .context
+            .get_state_value_version(&StateKey::Raw(b"missing".to_vec()), version)
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_get_account_resource_bytes() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let root_address = context.root_account().address();
+
+    let bytes = context
+        .context
+        .get_account_resource_bytes(root_address, &AccountResource::struct_tag(), version)
+        .unwrap()
+        .unwrap();
+    let account: AccountResource = bcs::from_bytes(&bytes).unwrap();
+    assert_eq!(account.sequence_number(), 0);
+
+    let missing = AccountAddress::random();
+    assert_eq!(
+        context
+            .context
+            .get_account_resource_bytes(missing, &AccountResource::struct_tag(), version)
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_get_table_item_by_raw_key() {
+    let mut context = new_test_context(current_function_name!());
+    let ctx = &mut context;
+    let mut account = ctx.gen_account();
+    let acc = &mut account;
+    let txn = ctx.create_user_account(acc);
+    ctx.commit_block(&vec![txn.clone()]).await;
+    make_test_tables(ctx, acc).await;
+
+    let tt = ctx
+        .api_get_account_resource(
+            acc,
+            format!(
+                "{}::TableTestData::TestTables",
+                acc.address().to_hex_literal()
+            ),
+        )
+        .await["data"]
+        .to_owned();
+    let handle = TableHandle(tt["u64_table"]["handle"].as_str().unwrap().parse().unwrap());
+    let version = ctx.get_latest_ledger_info().version();
+
+    let value = ctx
+        .context
+        .get_table_item(handle, &bcs::to_bytes(&1u64).unwrap(), version)
+        .unwrap()
+        .unwrap();
+    assert_eq!(bcs::from_bytes::<u64>(&value).unwrap(), 1);
+    assert_eq!(
+        ctx.context
+            .get_table_item(handle, &bcs::to_bytes(&2u64).unwrap(), version)
+            .unwrap(),
+        None
+    );
+    assert!(ctx.context.get_table_item(handle, &[], version).is_err());
+}
+
+#[tokio::test]
+async fn test_get_resource_group() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let root_address = context.root_account().address();
+    // Declared without fields and with `key`, like resource groups are
+    let group_tag = match parse_type_tag("0x1::aptos_coin::AptosCoin").unwrap() {
+        TypeTag::Struct(struct_tag) => struct_tag,
+        _ => unreachable!(),
+    };
+    let resource_key = |address, struct_tag: &StructTag| {
+        StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            address,
+            struct_tag.clone(),
+        )))
+    };
+
+    // Not declared as a group, whether the account has it or not
+    let missing = AccountAddress::random();
+    for address in [root_address, missing] {
+        let err = context
+            .context
+            .get_resource_group(address, &AccountResource::struct_tag(), version)
+            .unwrap_err();
+        assert!(err.to_string().contains("is not a resource group"));
+    }
+    assert!(context
+        .context
+        .get_resource_group(missing, &group_tag, version)
+        .unwrap()
+        .is_empty());
+
+    // `[0x00]`, the encoding of an empty map as well as of the group struct's `dummy_field`, is
+    // the group struct itself
+    let plain = AccountAddress::random();
+    let group = AccountAddress::random();
+    let member = (AccountResource::struct_tag(), vec![1, 2, 3]);
+    let members: BTreeMap<_, _> = vec![member].into_iter().collect();
+    let synthetic = context_with_db(StateValuesDb::over(
+        context.db.clone(),
+        vec![
+            (
+                resource_key(plain, &group_tag),
+                StateValue::from(vec![0x00]),
+            ),
+            (
+                resource_key(group, &group_tag),
+                StateValue::from(bcs::to_bytes(&members).unwrap()),
+            ),
+        ],
+    ));
+    let err = synthetic
+        .get_resource_group(plain, &group_tag, version)
+        .unwrap_err();
+    assert!(err.to_string().contains("is not a resource group"));
+    assert_eq!(
+        synthetic
+            .get_resource_group(group, &group_tag, version)
+            .unwrap(),
+        members
+    );
+}
+
+#[test]
+fn test_get_table_items_pages() {
+    let handle = TableHandle(1);
+    let items: Vec<_> = (0u64..5)
+        .map(|i| {
+            (
+                bcs::to_bytes(&i).unwrap(),
+                bcs::to_bytes(&(i * 10)).unwrap(),
+            )
+        })
+        .collect();
+    let context = context_with_db(StateValuesDb::new(
+        items
+            .iter()
+            .map(|(key, value)| {
+                (
+                    StateKey::table_item(handle, key.clone()),
+                    StateValue::from(value.clone()),
+                )
+            })
+            .chain(std::iter::once((
+                StateKey::table_item(TableHandle(2), bcs::to_bytes(&0u64).unwrap()),
+                StateValue::from(vec![0]),
+            ))),
+    ));
+
+    let mut paged = Vec::new();
+    let mut cursor = None;
+    let mut pages = 0;
+    loop {
+        let (page, next_cursor) = context.get_table_items(handle, 0, cursor, 2).unwrap();
+        assert!(page.len() <= 2);
+        paged.extend(page);
+        pages += 1;
+        cursor = next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(pages, 3);
+    paged.sort();
+    assert_eq!(paged, items);
+}
+
 fn get_account_resource(address: &str, struct_tag: &str) -> String {
     format!("/accounts/{}/resource/{}", address, struct_tag)
 }
@@ -50,6 +50,13 @@ use vm_validator::vm_validator::VMValidator;
 use warp::http::header::CONTENT_TYPE;
 
 pub fn new_test_context(test_name: &'static str) -> TestContext {
+    new_test_context_with_config(test_name, NodeConfig::default())
+}
+
+pub fn new_test_context_with_config(
+    test_name: &'static str,
+    node_config: NodeConfig,
+) -> TestContext {
     let tmp_dir = TempPath::new();
     tmp_dir.create_as_dir().unwrap();
 
@@ -91,7 +98,7 @@ pub fn new_test_context(test_name: &'static str) -> TestContext {
             ChainId::test(),
             db.clone(),
             mempool.ac_client.clone(),
-            NodeConfig::default(),
+            node_config,
         ),
         rng,
         root_key,
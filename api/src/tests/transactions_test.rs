@@ -2,11 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    caching_db_reader::CachingDbReader,
+    circuit_breaker::MempoolUnavailable,
+    context::{
+        Context, DecodedEntryFunction, FormattedResponse, ReadTimeout, ResponseFormat,
+        ResponseSizeExceeded, SubmissionOutcome, TransactionLookup, TransactionTooLarge,
+        TransactionWaitCancelled, VersionUnavailable,
+    },
     current_function_name,
-    tests::{assert_json, new_test_context, pretty, TestContext},
+    tests::{assert_json, new_test_context, new_test_context_with_config, pretty, TestContext},
 };
 
-use aptos_api_types::HexEncodedBytes;
+use aptos_api_types::{HexEncodedBytes, TransactionOnChainData};
+use aptos_config::config::NodeConfig;
 use aptos_crypto::{
     multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey},
     PrivateKey, SigningKey, Uniform,
@@ -15,23 +23,27 @@ use aptos_sdk::types::LocalAccount;
 use aptos_types::{
     access_path::{AccessPath, Path},
     account_address::AccountAddress,
+    chain_id::ChainId,
     transaction::{
         authenticator::{AuthenticationKey, TransactionAuthenticator},
-        ChangeSet, Script, ScriptFunction, SignedTransaction,
+        ChangeSet, ExecutionStatus, Script, ScriptFunction, SignedTransaction,
     },
     utility_coin::APTOS_COIN_TYPE,
     write_set::{WriteOp, WriteSetMut},
 };
 
 use aptos_crypto::ed25519::Ed25519PrivateKey;
-use aptos_types::state_store::state_key::StateKey;
+use aptos_types::state_store::{state_key::StateKey, table::TableHandle};
 use cached_framework_packages::aptos_stdlib;
+use futures::{channel::mpsc, StreamExt};
 use move_deps::move_core_types::{
     identifier::Identifier,
     language_storage::{ModuleId, StructTag, TypeTag},
 };
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use serde_json::json;
+use storage_interface::DbReader;
+use tokio_util::sync::CancellationToken;
 
 #[tokio::test]
 async fn test_deserialize_genesis_transaction() {
@@ -629,6 +641,1555 @@ async fn test_signing_message_with_write_set_payload() {
         .await;
 }
 
+#[tokio::test]
+async fn test_get_transactions_bcs_matches_json_path() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let limit = (ledger_version + 1) as u16;
+    let on_chain = context
+        .context
+        .get_transactions(0, limit, ledger_version)
+        .unwrap();
+    let raw = context
+        .context
+        .get_transactions_bcs(0, limit, ledger_version)
+        .unwrap();
+
+    assert_eq!(on_chain.len(), raw.len());
+    for (data, (txn, info, events, write_set)) in on_chain.into_iter().zip(raw) {
+        assert_eq!(data.transaction, txn);
+        assert_eq!(data.info, info);
+        assert_eq!(data.events, events);
+        assert_eq!(data.changes, write_set);
+    }
+}
+
+#[tokio::test]
+async fn test_get_block_info_by_hash() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let block_info = context
+        .context
+        .get_block_info(ledger_version, ledger_version)
+        .unwrap();
+    let by_hash = context
+        .context
+        .get_block_info_by_hash(block_info.block_hash.into(), ledger_version)
+        .unwrap();
+    assert_eq!(by_hash.start_version, block_info.start_version);
+    assert_eq!(by_hash.end_version, block_info.end_version);
+    assert_eq!(by_hash.block_height, block_info.block_height);
+
+    assert!(context
+        .context
+        .get_block_info_by_hash(aptos_crypto::HashValue::random(), ledger_version)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_get_transactions_latest() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let (ledger_info, txns) = context.context.get_transactions_latest(0, 1000).unwrap();
+    assert_eq!(
+        ledger_info.version(),
+        context.get_latest_ledger_info().version()
+    );
+    assert_eq!(txns.len() as u64, ledger_info.version() + 1);
+}
+
+#[tokio::test]
+async fn test_extract_participants() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let txns = context.get_transactions(0, (ledger_version + 1) as u16);
+    // Genesis has no sender
+    assert_eq!(crate::context::extract_participants(&txns[0]).sender, None);
+    let user_txn = txns
+        .iter()
+        .find(|txn| {
+            matches!(
+                txn.transaction,
+                aptos_types::transaction::Transaction::UserTransaction(_)
+            )
+        })
+        .unwrap();
+    assert_eq!(
+        crate::context::extract_participants(user_txn).sender,
+        Some(context.root_account().address())
+    );
+}
+
+#[tokio::test]
+async fn test_get_transaction_by_version_beyond_ledger_version() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let error = context
+        .context
+        .get_transaction_by_version(ledger_version + 1, ledger_version)
+        .unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<VersionUnavailable>(),
+        Some(&VersionUnavailable::NotFound {
+            version: ledger_version + 1,
+            ledger_version,
+        })
+    );
+}
+
+#[test]
+fn test_version_unavailable_check() {
+    assert_eq!(VersionUnavailable::check(5, 0, 10), Ok(()));
+    assert_eq!(VersionUnavailable::check(10, 10, 10), Ok(()));
+    assert_eq!(
+        VersionUnavailable::check(11, 0, 10),
+        Err(VersionUnavailable::NotFound {
+            version: 11,
+            ledger_version: 10,
+        })
+    );
+    assert_eq!(
+        VersionUnavailable::check(4, 5, 10),
+        Err(VersionUnavailable::Pruned {
+            version: 4,
+            oldest_version: 5,
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_get_transaction_by_hash_cached() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.transaction_by_hash_cache_capacity = Some(10);
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let hash = txn.committed_hash();
+    let ledger_version = context.get_latest_ledger_info().version();
+    let committed = context
+        .context
+        .get_transaction_by_hash(hash, ledger_version)
+        .unwrap()
+        .unwrap();
+    // Served from the cache this time
+    assert_eq!(
+        context
+            .context
+            .get_transaction_by_hash(hash, ledger_version)
+            .unwrap(),
+        Some(committed.clone())
+    );
+    // The cached transaction didn't exist yet as of the previous version
+    assert_eq!(
+        context
+            .context
+            .get_transaction_by_hash(hash, committed.version - 1)
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_get_transactions_range() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let txns = context
+        .context
+        .get_transactions_range(1, ledger_version, ledger_version)
+        .unwrap();
+    assert_eq!(txns.len() as u64, ledger_version);
+    assert_eq!(txns.first().unwrap().version, 1);
+    assert_eq!(txns.last().unwrap().version, ledger_version);
+
+    assert!(context
+        .context
+        .get_transactions_range(0, ledger_version + 1, ledger_version)
+        .is_err());
+    assert!(context
+        .context
+        .get_transactions_range(1, 0, ledger_version)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_get_transactions_range_limited_by_configured_page_size() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_transactions_page_size = Some(1);
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let txns = context
+        .context
+        .get_transactions_range(ledger_version, ledger_version, ledger_version)
+        .unwrap();
+    assert_eq!(txns.len(), 1);
+    let err = context
+        .context
+        .get_transactions_range(ledger_version - 1, ledger_version, ledger_version)
+        .unwrap_err();
+    assert!(err.to_string().contains("exceeds the limit 1"));
+}
+
+#[tokio::test]
+async fn test_get_block_info_beyond_ledger_version() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let error = context
+        .context
+        .get_block_info(ledger_version + 1, ledger_version)
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<VersionUnavailable>(),
+        Some(VersionUnavailable::NotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_lookup_transaction_by_hash() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let lookup = context
+        .context
+        .lookup_transaction_by_hash(txn.committed_hash(), ledger_version)
+        .await
+        .unwrap();
+    assert!(matches!(lookup, TransactionLookup::Committed(_)));
+
+    let lookup = context
+        .context
+        .lookup_transaction_by_hash(aptos_crypto::HashValue::random(), ledger_version)
+        .await
+        .unwrap();
+    assert_eq!(lookup, TransactionLookup::Unknown);
+}
+
+#[tokio::test]
+async fn test_get_block_gas_used() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let block_info = context
+        .context
+        .get_block_info(ledger_version, ledger_version)
+        .unwrap();
+    let expected: u64 = context
+        .context
+        .get_transactions(
+            block_info.start_version,
+            block_info.num_transactions,
+            ledger_version,
+        )
+        .unwrap()
+        .iter()
+        .map(|txn| txn.info.gas_used())
+        .sum();
+    assert!(expected > 0);
+    assert_eq!(
+        context
+            .context
+            .get_block_gas_used(block_info.block_height, ledger_version)
+            .unwrap(),
+        expected
+    );
+
+    assert_eq!(
+        context
+            .context
+            .get_block_gas_used(0, ledger_version)
+            .unwrap(),
+        0
+    );
+    assert!(context
+        .context
+        .get_block_gas_used(block_info.block_height + 1, ledger_version)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_replay_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let stored = context
+        .context
+        .get_transaction_by_hash(txn.committed_hash(), ledger_version)
+        .unwrap()
+        .unwrap();
+    let replayed = context.context.replay_transaction(stored.version).unwrap();
+    assert!(replayed.write_set_matches);
+    assert_eq!(replayed.data, stored);
+
+    assert!(context.context.replay_transaction(0).is_err());
+}
+
+#[tokio::test]
+async fn test_read_with_timeout() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_read_duration_ms = Some(10);
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+
+    let error = context
+        .context
+        .read_with_timeout(|_| {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+            Ok(())
+        })
+        .await
+        .unwrap_err();
+    assert!(error.is::<ReadTimeout>());
+
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let txns = context
+        .context
+        .get_transactions_with_timeout(0, 1, ledger_version)
+        .await
+        .unwrap();
+    assert_eq!(txns.len(), 1);
+}
+
+#[tokio::test]
+async fn test_heavy_routes_time_out() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_read_duration_ms = Some(0);
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+
+    // With no time allowed, the reads are given up on before they can finish
+    let timed_out = context.expect_status_code(408);
+    let resp = timed_out.get("/transactions").await;
+    assert!(resp["message"]
+        .as_str()
+        .unwrap()
+        .contains("did not complete"));
+    timed_out.get("/accounts/0x1/resources").await;
+    timed_out.get("/accounts/0x1/modules").await;
+}
+
+#[tokio::test]
+async fn test_get_account_transactions_in_range() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root = context.root_account();
+    let root_address = root.address();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root, &account);
+    context.commit_block(&vec![txn]).await;
+    let first_block_version = context.get_latest_ledger_info().version();
+
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root, &account);
+    context.commit_block(&vec![txn.clone()]).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let txns = context
+        .context
+        .get_account_transactions_in_range(
+            root_address,
+            0,
+            100,
+            first_block_version + 1,
+            ledger_version,
+        )
+        .unwrap();
+    assert_eq!(txns.len(), 1);
+    assert_eq!(
+        txns[0].transaction,
+        aptos_types::transaction::Transaction::UserTransaction(txn)
+    );
+
+    let txns = context
+        .context
+        .get_account_transactions_in_range(root_address, 0, 100, 0, first_block_version)
+        .unwrap();
+    assert!(txns.iter().all(|txn| txn.version <= first_block_version));
+    assert!(!txns.is_empty());
+
+    assert!(context
+        .context
+        .get_account_transactions_in_range(root_address, 0, 100, ledger_version, 0)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_get_transactions_formatted() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let limit = context.context.limit_to_ledger(0, 10, ledger_version);
+    let txns = context
+        .context
+        .get_transactions(0, limit, ledger_version)
+        .unwrap();
+
+    assert_eq!(
+        context
+            .context
+            .get_transactions_formatted(0, limit, ledger_version, ResponseFormat::Json)
+            .unwrap(),
+        FormattedResponse::Json(txns.clone())
+    );
+    match context
+        .context
+        .get_transactions_formatted(0, limit, ledger_version, ResponseFormat::Bcs)
+        .unwrap()
+    {
+        FormattedResponse::Bcs(bytes) => {
+            assert_eq!(
+                bcs::from_bytes::<Vec<TransactionOnChainData>>(&bytes).unwrap(),
+                txns
+            );
+        }
+        FormattedResponse::Json(_) => panic!("expected BCS"),
+    }
+}
+
+#[tokio::test]
+async fn test_get_block_info_num_user_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let block_info = context
+        .context
+        .get_block_info(ledger_version, ledger_version)
+        .unwrap();
+    assert_eq!(block_info.num_user_transactions, 1);
+    assert!(block_info.num_transactions > block_info.num_user_transactions);
+
+    let genesis = context.context.get_block_info(0, ledger_version).unwrap();
+    assert_eq!(genesis.num_user_transactions, 0);
+}
+
+#[tokio::test]
+async fn test_get_block_info_proposer() {
+    let mut context = new_test_context(current_function_name!());
+    context.commit_block(&[]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let block_info = context
+        .context
+        .get_block_info(ledger_version, ledger_version)
+        .unwrap();
+    assert_eq!(block_info.proposer, Some(context.validator_owner.into()));
+
+    // Genesis has no proposer
+    let genesis = context.context.get_block_info(0, ledger_version).unwrap();
+    assert_eq!(genesis.proposer, None);
+}
+
+#[tokio::test]
+async fn test_get_transactions_by_hashes() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let hash = txn.committed_hash();
+    let missing = aptos_crypto::HashValue::random();
+    let committed = context
+        .context
+        .get_transaction_by_hash(hash, ledger_version)
+        .unwrap();
+    assert!(committed.is_some());
+
+    let txns = context
+        .context
+        .get_transactions_by_hashes(&[missing, hash, missing, hash], ledger_version)
+        .unwrap();
+    assert_eq!(txns, vec![None, committed.clone(), None, committed.clone()]);
+
+    // Transactions of different blocks come back in the order they were asked for
+    let other_account = context.gen_account();
+    let other_txn = context.create_user_account(&other_account);
+    context.commit_block(&vec![other_txn.clone()]).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+    let other_hash = other_txn.committed_hash();
+    let other_committed = context
+        .context
+        .get_transaction_by_hash(other_hash, ledger_version)
+        .unwrap();
+    assert!(other_committed.is_some());
+
+    let txns = context
+        .context
+        .get_transactions_by_hashes(&[other_hash, missing, hash], ledger_version)
+        .unwrap();
+    assert_eq!(txns, vec![other_committed, None, committed]);
+}
+
+#[tokio::test]
+async fn test_submit_transaction_rejects_too_large_transaction() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.submit_transaction_content_length_limit = Some(10);
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+
+    let error = context.context.submit_transaction(txn).await.unwrap_err();
+    let too_large = error.downcast_ref::<TransactionTooLarge>().unwrap();
+    assert_eq!(too_large.limit, 10);
+    assert!(too_large.size > 10);
+}
+
+#[tokio::test]
+async fn test_is_version_available() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    assert_eq!(
+        context.context.get_version_range().unwrap(),
+        (0, ledger_version)
+    );
+    assert!(context.context.is_version_available(0).unwrap());
+    assert!(context
+        .context
+        .is_version_available(ledger_version)
+        .unwrap());
+    assert!(!context
+        .context
+        .is_version_available(ledger_version + 1)
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_submit_transaction_typed() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    assert_eq!(
+        context.context.submit_transaction_typed(txn).await.unwrap(),
+        SubmissionOutcome::Accepted
+    );
+}
+
+#[test]
+fn test_submission_outcome_from_status() {
+    use aptos_types::{
+        mempool_status::{MempoolStatus, MempoolStatusCode},
+        vm_status::StatusCode,
+    };
+
+    let outcome = |code, vm_status| SubmissionOutcome::from((MempoolStatus::new(code), vm_status));
+    assert_eq!(
+        outcome(MempoolStatusCode::MempoolIsFull, None),
+        SubmissionOutcome::MempoolIsFull
+    );
+    assert_eq!(
+        outcome(MempoolStatusCode::InvalidSeqNumber, None),
+        SubmissionOutcome::SequenceNumberTooOld
+    );
+    assert_eq!(
+        outcome(
+            MempoolStatusCode::VmError,
+            Some(StatusCode::SEQUENCE_NUMBER_TOO_NEW)
+        ),
+        SubmissionOutcome::SequenceNumberTooNew
+    );
+    assert_eq!(
+        outcome(
+            MempoolStatusCode::VmError,
+            Some(StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE)
+        ),
+        SubmissionOutcome::InsufficientBalanceForTransactionFee
+    );
+    let rejected = outcome(MempoolStatusCode::InvalidUpdate, None);
+    assert!(matches!(rejected, SubmissionOutcome::Rejected { .. }));
+    assert!(rejected.error_code().is_some());
+    assert!(SubmissionOutcome::Accepted.error_code().is_none());
+}
+
+#[tokio::test]
+async fn test_account_transactions_conversion_matches_sequential() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root = context.root_account();
+    for _ in 0..20 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root, &account);
+        context.commit_block(&vec![txn]).await;
+    }
+    let mut node_config = NodeConfig::default();
+    node_config.api.transaction_conversion_threads = Some(1);
+    let (mp_sender, _mp_receiver) = mpsc::channel(1);
+    let sequential = Context::new(ChainId::test(), context.db.clone(), mp_sender, node_config);
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let parallel_txns = context
+        .context
+        .get_account_transactions(root.address(), 0, 100, ledger_version)
+        .unwrap();
+    let sequential_txns = sequential
+        .get_account_transactions(root.address(), 0, 100, ledger_version)
+        .unwrap();
+    // Converted concurrently, but still in the order of the page
+    assert!(parallel_txns.len() >= 20);
+    assert!(parallel_txns
+        .windows(2)
+        .all(|pair| pair[0].version < pair[1].version));
+    assert_eq!(parallel_txns, sequential_txns);
+}
+
+#[tokio::test]
+async fn test_get_block_info_cached() {
+    let mut context = new_test_context(current_function_name!());
+    let previous_version = context.get_latest_ledger_info().version();
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let block_info = context
+        .context
+        .get_block_info(ledger_version, ledger_version)
+        .unwrap();
+    // Served from the cache this time
+    let cached = context
+        .context
+        .get_block_info(block_info.start_version, ledger_version)
+        .unwrap();
+    assert_eq!(cached.block_hash, block_info.block_hash);
+    assert_eq!(cached.block_height, block_info.block_height);
+    // The cached block didn't exist yet as of the previous version
+    assert!(context
+        .context
+        .get_block_info(ledger_version, previous_version)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_get_transaction_by_hash_with_proof() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let ledger_info = context
+        .context
+        .get_latest_ledger_info_with_signatures()
+        .unwrap();
+    let ledger_info = ledger_info.ledger_info();
+    let with_proof = context
+        .context
+        .get_transaction_by_hash_with_proof(txn.committed_hash(), ledger_info.version())
+        .unwrap()
+        .unwrap();
+    with_proof
+        .verify_user_txn(
+            ledger_info,
+            with_proof.version,
+            txn.sender(),
+            txn.sequence_number(),
+        )
+        .unwrap();
+
+    assert!(context
+        .context
+        .get_transaction_by_hash_with_proof(
+            aptos_crypto::HashValue::random(),
+            ledger_info.version()
+        )
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_get_successful_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let mut root = context.root_account();
+    let start_version = context.get_latest_ledger_info().version() + 1;
+    let create_txn = context.create_user_account_by(&mut root, &account);
+    // Creating the same account again aborts, but is still committed
+    let duplicate_create_txn = context.create_user_account_by(&mut root, &account);
+    let mint_txn = root
+        .sign_with_transaction_builder(context.transaction_factory().mint(account.address(), 1000));
+    context
+        .commit_block(&vec![
+            create_txn.clone(),
+            duplicate_create_txn.clone(),
+            mint_txn.clone(),
+        ])
+        .await;
+    let ledger_version = context.get_latest_ledger_info().version();
+    let limit = (ledger_version - start_version + 1) as u16;
+
+    let all = context
+        .context
+        .get_transactions(start_version, limit, ledger_version)
+        .unwrap();
+    let successful = context
+        .context
+        .get_successful_transactions(start_version, limit, ledger_version)
+        .unwrap();
+    assert_eq!(successful.len(), all.len() - 1);
+    assert!(successful.iter().all(|txn| txn.info.status().is_success()));
+
+    let user_txn_hashes: Vec<_> = successful
+        .iter()
+        .filter_map(|txn| match &txn.transaction {
+            aptos_types::transaction::Transaction::UserTransaction(txn) => {
+                Some(txn.clone().committed_hash())
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        user_txn_hashes,
+        vec![create_txn.committed_hash(), mint_txn.committed_hash()]
+    );
+}
+
+#[tokio::test]
+async fn test_is_epoch_boundary() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    // Genesis starts the first epoch
+    assert!(context.context.is_epoch_boundary(0).unwrap());
+    let version = context.get_latest_ledger_info().version();
+    assert!(!context.context.is_epoch_boundary(version).unwrap());
+    assert!(context.context.is_epoch_boundary(version + 1).is_err());
+}
+
+#[tokio::test]
+async fn test_heavy_reads_rejected_when_overloaded() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_concurrent_heavy_reads = Some(1);
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let permit = context.context.acquire_heavy_read().unwrap();
+    let err = context
+        .context
+        .get_transactions(0, 1, ledger_version)
+        .unwrap_err();
+    assert!(err.is::<crate::context::Overloaded>());
+    assert!(context
+        .context
+        .get_state_values(AccountAddress::ONE, ledger_version)
+        .unwrap_err()
+        .is::<crate::context::Overloaded>());
+    assert!(context
+        .context
+        .get_state_values_parallel(AccountAddress::ONE, ledger_version)
+        .unwrap_err()
+        .is::<crate::context::Overloaded>());
+    assert!(context
+        .context
+        .get_table_items(TableHandle(1), ledger_version, None, 1)
+        .unwrap_err()
+        .is::<crate::context::Overloaded>());
+    assert!(context
+        .context
+        .get_account_transactions(AccountAddress::ONE, 0, 1, ledger_version)
+        .unwrap_err()
+        .is::<crate::context::Overloaded>());
+    assert!(context
+        .context
+        .get_events_in_range(0, 1, ledger_version)
+        .unwrap_err()
+        .is::<crate::context::Overloaded>());
+    // Routes answer with 503 rather than a storage error
+    let overloaded = context.expect_status_code(503);
+    overloaded.get("/transactions?limit=1").await;
+    overloaded.get("/accounts/0x1/resources").await;
+
+    drop(permit);
+    assert_eq!(
+        context
+            .context
+            .get_transactions(0, 1, ledger_version)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_get_block_infos() {
+    let mut context = new_test_context(current_function_name!());
+    for _ in 0..3 {
+        context.commit_block(&[]).await;
+    }
+    let ledger_version = context.get_latest_ledger_info().version();
+    let latest = context
+        .context
+        .get_block_info(ledger_version, ledger_version)
+        .unwrap();
+
+    let block_infos = context
+        .context
+        .get_block_infos(0, 100, ledger_version)
+        .unwrap();
+    assert_eq!(block_infos.len() as u64, latest.block_height + 1);
+    for (height, block_info) in block_infos.iter().enumerate() {
+        assert_eq!(block_info.block_height, height as u64);
+        let expected = context
+            .context
+            .get_block_info(block_info.start_version, ledger_version)
+            .unwrap();
+        assert_eq!(block_info.end_version, expected.end_version);
+        assert_eq!(block_info.block_hash, expected.block_hash);
+    }
+
+    let block_infos = context
+        .context
+        .get_block_infos(1, 2, ledger_version)
+        .unwrap();
+    assert_eq!(
+        block_infos
+            .iter()
+            .map(|block_info| block_info.block_height)
+            .collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    assert!(context
+        .context
+        .get_block_infos(latest.block_height + 1, 1, ledger_version)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_get_block_infos_uncached_match_one_by_one() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.block_info_cache_capacity = Some(0);
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    for _ in 0..50 {
+        context.commit_block(&[]).await;
+    }
+    let ledger_version = context.get_latest_ledger_info().version();
+    let latest = context
+        .context
+        .get_block_info(ledger_version, ledger_version)
+        .unwrap();
+    let start_height = latest.block_height - 49;
+
+    // Resolved in parallel with nothing cached, but in height order all the same
+    let block_infos = context
+        .context
+        .get_block_infos(start_height, 50, ledger_version)
+        .unwrap();
+    assert_eq!(block_infos.len(), 50);
+    for (height, block_info) in (start_height..).zip(&block_infos) {
+        let expected = context
+            .context
+            .get_block_info(block_info.start_version, ledger_version)
+            .unwrap();
+        assert_eq!(block_info.block_height, height);
+        assert_eq!(block_info.block_height, expected.block_height);
+        assert_eq!(block_info.block_hash, expected.block_hash);
+        assert_eq!(block_info.block_timestamp, expected.block_timestamp);
+        assert_eq!(block_info.start_version, expected.start_version);
+        assert_eq!(block_info.end_version, expected.end_version);
+        assert_eq!(block_info.num_transactions, expected.num_transactions);
+        assert_eq!(
+            block_info.num_user_transactions,
+            expected.num_user_transactions
+        );
+        assert_eq!(block_info.proposer, expected.proposer);
+    }
+    assert_eq!(block_infos.last().unwrap().end_version, latest.end_version);
+}
+
+#[tokio::test]
+async fn test_get_genesis_transaction() {
+    let context = new_test_context(current_function_name!());
+    let genesis = context.context.get_genesis_transaction().unwrap();
+    assert_eq!(genesis.version, 0);
+    assert!(matches!(
+        genesis.transaction,
+        aptos_types::transaction::Transaction::GenesisTransaction(_)
+    ));
+    // Genesis publishes the framework
+    assert!(!genesis.changes.is_empty());
+}
+
+#[tokio::test]
+async fn test_transactions_limit_clamped_to_configured_max() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_transactions_page_size = Some(2);
+    node_config.api.max_account_transactions_page_size = Some(1);
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let mut root = context.root_account();
+    let first = context.gen_account();
+    let second = context.gen_account();
+    let txns = vec![
+        context.create_user_account_by(&mut root, &first),
+        context.create_user_account_by(&mut root, &second),
+    ];
+    context.commit_block(&txns).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    assert_eq!(
+        context
+            .context
+            .get_transactions(0, 100, ledger_version)
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        context
+            .context
+            .get_transactions_bcs(0, 100, ledger_version)
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        context
+            .context
+            .get_events_in_range(0, 100, ledger_version)
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        context
+            .context
+            .get_account_transactions(root.address(), 0, 100, ledger_version)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_get_block_for_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let genesis_block = context
+        .context
+        .get_block_for_transaction(0, ledger_version)
+        .unwrap();
+    assert_eq!(genesis_block.block_height, 0);
+    assert_eq!(genesis_block.start_version, 0);
+
+    let block = context
+        .context
+        .get_block_for_transaction(ledger_version, ledger_version)
+        .unwrap();
+    assert!(block.start_version <= ledger_version && ledger_version <= block.end_version);
+    assert!(block.block_height > 0);
+
+    assert!(context
+        .context
+        .get_block_for_transaction(ledger_version + 1, ledger_version)
+        .unwrap_err()
+        .is::<crate::context::VersionUnavailable>());
+}
+
+#[tokio::test]
+async fn test_verify_version_consistency() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    for version in [0, ledger_version] {
+        let report = context.context.verify_version_consistency(version).unwrap();
+        assert!(report.is_consistent(), "{:?}", report);
+        assert_eq!(report.end_version, version);
+        assert!(report.start_version <= version);
+    }
+    assert!(context
+        .context
+        .verify_version_consistency(ledger_version + 1)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_submit_transaction_async() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let hash = context
+        .context
+        .submit_transaction_async(txn.clone())
+        .await
+        .unwrap();
+    assert_eq!(hash, txn.committed_hash());
+
+    // The transaction reaches mempool some time after the call returns
+    let ledger_version = context.get_latest_ledger_info().version();
+    let mut lookup = TransactionLookup::Unknown;
+    for _ in 0..50 {
+        lookup = context
+            .context
+            .lookup_transaction_by_hash(hash, ledger_version)
+            .await
+            .unwrap();
+        if !matches!(lookup, TransactionLookup::Unknown) {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(matches!(lookup, TransactionLookup::Pending(_)));
+}
+
+#[tokio::test]
+async fn test_submit_transaction_async_mempool_failures_open_breaker() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let mut node_config = NodeConfig::default();
+    node_config.api.mempool_circuit_breaker_failure_threshold = Some(1);
+    node_config.api.mempool_circuit_breaker_cooldown_ms = Some(60_000);
+    node_config.api.mempool_submission_timeout_ms = Some(100);
+
+    // Mempool is gone, handing it the transaction fails
+    let (mp_sender, _) = mpsc::channel(1);
+    let gone = Context::new(
+        ChainId::test(),
+        context.db.clone(),
+        mp_sender,
+        node_config.clone(),
+    );
+    assert!(gone.submit_transaction_async(txn.clone()).await.is_err());
+    let err = gone
+        .submit_transaction_async(txn.clone())
+        .await
+        .unwrap_err();
+    assert!(err.is::<MempoolUnavailable>());
+
+    // Mempool takes the transaction but never answers
+    let (mp_sender, _mp_receiver) = mpsc::channel(1);
+    let stuck = Context::new(ChainId::test(), context.db.clone(), mp_sender, node_config);
+    stuck.submit_transaction_async(txn.clone()).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    let err = stuck.submit_transaction_async(txn).await.unwrap_err();
+    assert!(err.is::<MempoolUnavailable>());
+}
+
+#[tokio::test]
+async fn test_get_block_timestamp_cached() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let timestamp = context.context.get_block_timestamp(ledger_version).unwrap();
+    assert!(timestamp > 0);
+    // Served from the cache this time
+    assert_eq!(
+        context.context.get_block_timestamp(ledger_version).unwrap(),
+        timestamp
+    );
+    assert_eq!(
+        context.db.get_block_timestamp(ledger_version).unwrap(),
+        timestamp
+    );
+}
+
+#[tokio::test]
+async fn test_submit_transaction_bcs() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+
+    let (mempool_status, _) = context
+        .context
+        .submit_transaction_bcs(&bcs::to_bytes(&txn).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        mempool_status.code,
+        aptos_types::mempool_status::MempoolStatusCode::Accepted
+    );
+
+    let err = context
+        .context
+        .submit_transaction_bcs(&[0xde, 0xad])
+        .await
+        .unwrap_err();
+    assert!(err.is::<crate::context::InvalidTransactionEncoding>());
+}
+
+#[tokio::test]
+async fn test_estimate_transactions_response_size() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let estimate = |limit| {
+        context
+            .context
+            .estimate_transactions_response_size(0, limit, ledger_version)
+            .unwrap()
+    };
+    // Grows with the page, at least by the fixed size of each transaction
+    assert!(estimate(1) >= 1024);
+    assert!(estimate(100) >= estimate(1) + 1024 * ledger_version);
+    assert_eq!(
+        context
+            .context
+            .estimate_transactions_response_size(ledger_version + 1, 100, ledger_version)
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_get_transactions_rejected_over_response_size() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_transactions_response_bytes = Some(2048);
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let err = context
+        .context
+        .check_transactions_response_size(0, 100, context.get_latest_ledger_info().version())
+        .unwrap_err();
+    assert!(err.is::<ResponseSizeExceeded>());
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions?start=0&limit=100")
+        .await;
+    assert!(resp["message"]
+        .as_str()
+        .unwrap()
+        .contains("more than the maximum of 2048"));
+
+    // Small enough pages are still served
+    context
+        .expect_status_code(200)
+        .get("/transactions?start=0&limit=1")
+        .await;
+}
+
+#[tokio::test]
+async fn test_decode_entry_function() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let version = context.get_latest_ledger_info().version();
+
+    match context
+        .context
+        .decode_entry_function(&txn, version)
+        .unwrap()
+    {
+        DecodedEntryFunction::EntryFunction {
+            function,
+            type_arguments,
+            arguments,
+        } => {
+            assert_eq!(function.name.0.as_str(), "create_account");
+            assert!(type_arguments.is_empty());
+            assert_eq!(arguments.len(), 1);
+            assert_eq!(
+                arguments[0].value,
+                Some(aptos_api_types::MoveValue::Address(
+                    account.address().into()
+                ))
+            );
+            assert_eq!(
+                arguments[0].bytes,
+                bcs::to_bytes(&account.address()).unwrap()
+            );
+        }
+        DecodedEntryFunction::NotAnEntryFunction => panic!("expected an entry function"),
+    }
+
+    let script_txn = context.root_account().sign_with_transaction_builder(
+        context
+            .transaction_factory()
+            .script(Script::new(vec![], vec![], vec![])),
+    );
+    assert_eq!(
+        context
+            .context
+            .decode_entry_function(&script_txn, version)
+            .unwrap(),
+        DecodedEntryFunction::NotAnEntryFunction
+    );
+}
+
+#[tokio::test]
+async fn test_block_info_cache_evicts_by_height_and_version() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.block_info_cache_capacity = Some(2);
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    for _ in 0..4 {
+        context.commit_block(&[]).await;
+    }
+    let ledger_version = context.get_latest_ledger_info().version();
+    let mut uncached_config = NodeConfig::default();
+    uncached_config.api.block_info_cache_capacity = Some(0);
+    let (mp_sender, _) = mpsc::channel(1);
+    let uncached = Context::new(
+        ChainId::test(),
+        context.db.clone(),
+        mp_sender,
+        uncached_config,
+    );
+
+    // Each pass evicts the blocks read by the previous one, through the version index and by
+    // height in turn
+    for _ in 0..2 {
+        for version in 0..=ledger_version {
+            let block_info = context
+                .context
+                .get_block_info(version, ledger_version)
+                .unwrap();
+            let expected = uncached.get_block_info(version, ledger_version).unwrap();
+            assert_eq!(block_info.block_height, expected.block_height);
+            assert_eq!(block_info.start_version, expected.start_version);
+            assert_eq!(block_info.end_version, expected.end_version);
+            assert_eq!(
+                context
+                    .context
+                    .count_block_height(block_info.start_version, ledger_version)
+                    .unwrap(),
+                block_info.block_height
+            );
+            assert_eq!(
+                context
+                    .context
+                    .get_block_gas_used(block_info.block_height, ledger_version)
+                    .unwrap(),
+                uncached
+                    .get_block_gas_used(block_info.block_height, ledger_version)
+                    .unwrap()
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_count_block_height() {
+    let mut node_config = NodeConfig::default();
+    // Count from genesis rather than from cached blocks
+    node_config.api.block_info_cache_capacity = Some(0);
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    for _ in 0..3 {
+        context.commit_block(&[]).await;
+    }
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let block_infos = context
+        .context
+        .get_block_infos(0, 100, ledger_version)
+        .unwrap();
+    for block_info in &block_infos {
+        assert_eq!(
+            context
+                .context
+                .count_block_height(block_info.start_version, ledger_version)
+                .unwrap(),
+            block_info.block_height
+        );
+    }
+
+    // Versions within a block don't start one
+    let block_info = block_infos
+        .iter()
+        .find(|block_info| block_info.end_version > block_info.start_version)
+        .unwrap();
+    assert!(context
+        .context
+        .count_block_height(block_info.start_version + 1, ledger_version)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_get_writes_for_address() {
+    let mut context = new_test_context(current_function_name!());
+    let start_version = context.get_latest_ledger_info().version() + 1;
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    // Only the account creation writes under the new account
+    let writes = context
+        .context
+        .get_writes_for_address(account.address(), start_version, 100, ledger_version)
+        .unwrap();
+    assert_eq!(writes.len(), 1);
+    let (version, ops) = &writes[0];
+    assert!((start_version..=ledger_version).contains(version));
+    assert!(!ops.is_empty());
+
+    let other = context.gen_account();
+    assert!(context
+        .context
+        .get_writes_for_address(other.address(), start_version, 100, ledger_version)
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_submit_and_wait_cancelled() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+
+    // Nothing commits blocks here, so only cancelling ends the wait early
+    let cancel = CancellationToken::new();
+    let canceller = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        canceller.cancel();
+    });
+    let err = context
+        .context
+        .submit_and_wait(txn.clone(), std::time::Duration::from_secs(60), cancel)
+        .await
+        .unwrap_err();
+    let cancelled = err.downcast_ref::<TransactionWaitCancelled>().unwrap();
+    assert_eq!(cancelled.hash, txn.committed_hash());
+}
+
+#[tokio::test]
+async fn test_get_transactions_version_range_boundaries() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    // A page reaching past the ledger version is rejected, the last page is read by bounding
+    // the limit to what's left
+    let err = context
+        .context
+        .get_transactions(ledger_version, 10, ledger_version)
+        .unwrap_err();
+    assert!(err.to_string().contains("ends past the ledger version"));
+    let limit = context
+        .context
+        .limit_to_ledger(ledger_version, 10, ledger_version);
+    assert_eq!(limit, 1);
+    let txns = context
+        .context
+        .get_transactions(ledger_version, limit, ledger_version)
+        .unwrap();
+    assert_eq!(txns.len(), 1);
+
+    let err = context
+        .context
+        .get_transactions(ledger_version + 1, 10, ledger_version)
+        .unwrap_err();
+    assert!(err.to_string().contains("beyond the ledger version"));
+
+    // Near the max, pages are rejected before reaching storage rather than wrapping around
+    let err = context
+        .context
+        .get_transactions(u64::MAX - 1, 10, u64::MAX)
+        .unwrap_err();
+    assert!(err.to_string().contains("overflows the version range"));
+    let err = context
+        .context
+        .get_transactions(u64::MAX - 10, 5, u64::MAX - 8)
+        .unwrap_err();
+    assert!(err.to_string().contains("ends past the ledger version"));
+    assert_eq!(
+        context.context.limit_to_ledger(u64::MAX - 1, 10, u64::MAX),
+        2
+    );
+    assert_eq!(context.context.limit_to_ledger(0, 10, u64::MAX), 10);
+}
+
+#[tokio::test]
+async fn test_stream_blocks() {
+    let mut context = new_test_context(current_function_name!());
+    context.commit_block(&[]).await;
+    context.commit_block(&[]).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+    let latest = context
+        .context
+        .get_block_info(ledger_version, ledger_version)
+        .unwrap();
+
+    let blocks = context
+        .context
+        .stream_blocks(1, std::time::Duration::from_millis(10));
+    futures::pin_mut!(blocks);
+    let mut previous: Option<aptos_api_types::BlockInfo> = None;
+    for height in 1..=latest.block_height {
+        let block_info = blocks.next().await.unwrap().unwrap();
+        assert_eq!(block_info.block_height, height);
+        if let Some(previous) = previous {
+            assert_eq!(block_info.start_version, previous.end_version + 1);
+        }
+        previous = Some(block_info);
+    }
+
+    // Blocks committed later are yielded once they are
+    context.commit_block(&[]).await;
+    let block_info = blocks.next().await.unwrap().unwrap();
+    assert_eq!(block_info.block_height, latest.block_height + 1);
+    assert_eq!(
+        block_info.end_version,
+        context.get_latest_ledger_info().version()
+    );
+}
+
+#[tokio::test]
+async fn test_get_transaction_status() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let mut root = context.root_account();
+    let start_version = context.get_latest_ledger_info().version() + 1;
+    let create_txn = context.create_user_account_by(&mut root, &account);
+    // Creating the same account again aborts, but is still committed
+    let duplicate_create_txn = context.create_user_account_by(&mut root, &account);
+    context
+        .commit_block(&vec![create_txn, duplicate_create_txn])
+        .await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    // The block starts with its metadata transaction
+    let metadata = context
+        .context
+        .get_transaction_status(start_version, ledger_version)
+        .unwrap();
+    assert!(!metadata.is_user_transaction);
+    assert!(metadata.success);
+
+    let created = context
+        .context
+        .get_transaction_status(start_version + 1, ledger_version)
+        .unwrap();
+    assert!(created.is_user_transaction);
+    assert!(created.success);
+    assert!(created.gas_used > 0);
+
+    let duplicate = context
+        .context
+        .get_transaction_status(start_version + 2, ledger_version)
+        .unwrap();
+    assert!(duplicate.is_user_transaction);
+    assert!(!duplicate.success);
+    assert!(matches!(
+        duplicate.status,
+        ExecutionStatus::MoveAbort { .. }
+    ));
+
+    let genesis = context
+        .context
+        .get_transaction_status(0, ledger_version)
+        .unwrap();
+    assert!(!genesis.is_user_transaction);
+    assert_eq!(genesis.gas_used, 0);
+}
+
+#[tokio::test]
+async fn test_caching_db_reader() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let db = context.context.db.clone();
+    let cached = CachingDbReader::new(db.clone(), 10);
+    // Versions are cached once a ledger info read through the reader shows them committed
+    cached.get_latest_ledger_info().unwrap();
+    let start_version = ledger_version - 1;
+    for _ in 0..2 {
+        for fetch_events in [true, false] {
+            assert_eq!(
+                cached
+                    .get_transactions_without_proof(start_version, 2, ledger_version, fetch_events)
+                    .unwrap(),
+                db.get_transactions_without_proof(start_version, 2, ledger_version, fetch_events)
+                    .unwrap()
+            );
+        }
+        assert_eq!(
+            cached.get_accumulator_root_hash(ledger_version).unwrap(),
+            db.get_accumulator_root_hash(ledger_version).unwrap()
+        );
+    }
+
+    // Latest ledger reads pass through
+    context.commit_block(&[]).await;
+    assert_eq!(
+        cached.get_latest_version().unwrap(),
+        context.get_latest_ledger_info().version()
+    );
+}
+
+#[tokio::test]
+async fn test_get_transactions_without_events() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let limit = context.context.limit_to_ledger(0, 100, ledger_version);
+
+    let with_events = context
+        .context
+        .get_transactions_opts(0, limit, ledger_version, true)
+        .unwrap();
+    let without_events = context
+        .context
+        .get_transactions_opts(0, limit, ledger_version, false)
+        .unwrap();
+    // Genesis emits events
+    assert!(with_events.iter().any(|txn| !txn.events.is_empty()));
+    assert_eq!(
+        with_events,
+        context
+            .context
+            .get_transactions(0, limit, ledger_version)
+            .unwrap()
+    );
+    assert_eq!(with_events.len(), without_events.len());
+    for (with, without) in with_events.into_iter().zip(without_events) {
+        assert!(without.events.is_empty());
+        assert_eq!(with.version, without.version);
+        assert_eq!(with.transaction, without.transaction);
+        assert_eq!(with.info, without.info);
+        assert_eq!(with.accumulator_root_hash, without.accumulator_root_hash);
+        assert_eq!(with.changes, without.changes);
+    }
+}
+
 async fn test_signing_message_with_payload(
     mut context: TestContext,
     txn: SignedTransaction,
@@ -1176,6 +2737,27 @@ async fn test_create_signing_message_rejects_no_content_length_request() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_transaction_by_hash_with_timestamp() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let (data, timestamp) = context
+        .context
+        .get_transaction_by_hash_with_timestamp(txn.clone().committed_hash(), ledger_version)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        data.transaction,
+        aptos_types::transaction::Transaction::UserTransaction(txn)
+    );
+    // The first block committed by the test context is stamped with fake time 1
+    assert_eq!(timestamp, 1);
+}
+
 fn gen_string(len: u64) -> String {
     let mut rng = thread_rng();
     std::iter::repeat(())
@@ -3,7 +3,11 @@
 
 use crate::{
     accept_type::AcceptType,
-    context::Context,
+    circuit_breaker::MempoolUnavailable,
+    context::{
+        Context, Overloaded, ReadTimeout, ResponseSizeExceeded, RouteKind, TransactionLookup,
+        TransactionTooLarge,
+    },
     failpoint::fail_point,
     metrics::metrics,
     page::Page,
@@ -104,7 +108,7 @@ pub fn simulate_json_transactions(context: Context) -> BoxedFilter<(impl Reply,)
     warp::path!("transactions" / "simulate")
         .and(warp::post())
         .and(warp::body::content_length_limit(
-            context.content_length_limit(),
+            context.content_length_limit_for(RouteKind::SimulateTransaction),
         ))
         .and(warp::body::json::<UserTransactionRequest>())
         .and(context.filter())
@@ -118,7 +122,7 @@ pub fn simulate_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)>
     warp::path!("transactions" / "simulate")
         .and(warp::post())
         .and(warp::body::content_length_limit(
-            context.content_length_limit(),
+            context.content_length_limit_for(RouteKind::SimulateTransaction),
         ))
         .and(warp::header::exact(
             CONTENT_TYPE.as_str(),
@@ -136,7 +140,7 @@ pub fn submit_json_transactions(context: Context) -> BoxedFilter<(impl Reply,)>
     warp::path!("transactions")
         .and(warp::post())
         .and(warp::body::content_length_limit(
-            context.content_length_limit(),
+            context.content_length_limit_for(RouteKind::SubmitTransaction),
         ))
         .and(warp::body::json::<UserTransactionRequest>())
         .and(context.filter())
@@ -155,7 +159,7 @@ pub fn submit_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions")
         .and(warp::post())
         .and(warp::body::content_length_limit(
-            context.content_length_limit(),
+            context.content_length_limit_for(RouteKind::SubmitTransaction),
         ))
         .and(warp::header::exact(
             CONTENT_TYPE.as_str(),
@@ -173,7 +177,7 @@ pub fn create_signing_message(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions" / "signing_message")
         .and(warp::post())
         .and(warp::body::content_length_limit(
-            context.content_length_limit(),
+            context.content_length_limit_for(RouteKind::SubmitTransaction),
         ))
         .and(warp::body::json::<UserCreateSigningMessageRequest>())
         .and(context.filter())
@@ -199,7 +203,7 @@ async fn handle_get_transactions(
     accept_type: AcceptType,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_transactions")?;
-    Ok(Transactions::new(context)?.list(page, accept_type)?)
+    Ok(Transactions::new(context)?.list(page, accept_type).await?)
 }
 
 async fn handle_get_account_transactions(
@@ -310,7 +314,19 @@ impl Transactions {
     }
 
     pub async fn create(self, txn: SignedTransaction) -> Result<impl Reply, Error> {
-        let (mempool_status, vm_status_opt) = self.context.submit_transaction(txn.clone()).await?;
+        let (mempool_status, vm_status_opt) = self
+            .context
+            .submit_transaction(txn.clone())
+            .await
+            .map_err(|e| {
+                if e.is::<TransactionTooLarge>() {
+                    Error::from_anyhow_error(StatusCode::PAYLOAD_TOO_LARGE, e)
+                } else if e.is::<MempoolUnavailable>() {
+                    Error::from_anyhow_error(StatusCode::SERVICE_UNAVAILABLE, e)
+                } else {
+                    Error::internal(e)
+                }
+            })?;
         match mempool_status.code {
             MempoolStatusCode::Accepted => {
                 let resolver = self.context.move_resolver()?;
@@ -370,7 +386,7 @@ impl Transactions {
         self.render_transactions(vec![simulated_txn], AcceptType::Json)
     }
 
-    pub fn list(self, page: Page, accept_type: AcceptType) -> Result<impl Reply, Error> {
+    pub async fn list(self, page: Page, accept_type: AcceptType) -> Result<impl Reply, Error> {
         let ledger_version = self.ledger_info.version();
         let limit = page.limit()?;
         let last_page_start = if ledger_version > (limit as u64) {
@@ -379,21 +395,52 @@ impl Transactions {
             0
         };
         let start_version = page.start(last_page_start, ledger_version)?;
+        let limit = self
+            .context
+            .limit_to_ledger(start_version, limit, ledger_version);
 
+        self.context
+            .check_transactions_response_size(start_version, limit, ledger_version)
+            .map_err(|e| {
+                if e.is::<ResponseSizeExceeded>() {
+                    Error::bad_request(e)
+                } else {
+                    Error::internal(e)
+                }
+            })?;
         let data = self
             .context
-            .get_transactions(start_version, limit, ledger_version)?;
+            .get_transactions_with_timeout(start_version, limit, ledger_version)
+            .await
+            .map_err(|e| {
+                if e.is::<ReadTimeout>() {
+                    Error::from_anyhow_error(StatusCode::REQUEST_TIMEOUT, e)
+                } else if e.is::<Overloaded>() {
+                    Error::from_anyhow_error(StatusCode::SERVICE_UNAVAILABLE, e)
+                } else {
+                    Error::internal(e)
+                }
+            })?;
 
         self.render_transactions(data, accept_type)
     }
 
     pub fn list_by_account(self, address: AddressParam, page: Page) -> Result<impl Reply, Error> {
-        let data = self.context.get_account_transactions(
-            address.parse("account address")?.into(),
-            page.start(0, u64::MAX)?,
-            page.limit()?,
-            self.ledger_info.version(),
-        )?;
+        let data = self
+            .context
+            .get_account_transactions(
+                address.parse("account address")?.into(),
+                page.start(0, u64::MAX)?,
+                page.limit()?,
+                self.ledger_info.version(),
+            )
+            .map_err(|e| {
+                if e.is::<Overloaded>() {
+                    Error::from_anyhow_error(StatusCode::SERVICE_UNAVAILABLE, e)
+                } else {
+                    Error::internal(e)
+                }
+            })?;
         self.render_transactions(data, AcceptType::Json)
     }
 
@@ -508,16 +555,14 @@ impl Transactions {
     // Although the mempool get transation is async, but looking up txn in database is a sync call,
     // thus we keep it simple and call them in sequence.
     async fn get_by_hash(&self, hash: aptos_crypto::HashValue) -> Result<Option<TransactionData>> {
-        let from_db = self
+        let lookup = self
             .context
-            .get_transaction_by_hash(hash, self.ledger_info.version())?;
-        Ok(match from_db {
-            None => self
-                .context
-                .get_pending_transaction_by_hash(hash)
-                .await?
-                .map(|t| t.into()),
-            _ => from_db.map(|t| t.into()),
+            .lookup_transaction_by_hash(hash, self.ledger_info.version())
+            .await?;
+        Ok(match lookup {
+            TransactionLookup::Committed(txn) => Some(TransactionData::OnChain(*txn)),
+            TransactionLookup::Pending(txn) => Some(TransactionData::Pending(txn)),
+            TransactionLookup::Unknown => None,
         })
     }
 }
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::HashValue;
+use crate::{Address, HashValue};
 use serde::{Deserialize, Serialize};
 
 /// A description of a block
@@ -13,4 +13,11 @@ pub struct BlockInfo {
     pub start_version: u64,
     pub end_version: u64,
     pub num_transactions: u16,
+    /// Number of user transactions in the block, excluding the block metadata and state
+    /// checkpoint transactions counted by `num_transactions`
+    #[serde(default)]
+    pub num_user_transactions: u16,
+    /// The validator that proposed the block, `None` for genesis which has no proposer
+    #[serde(default)]
+    pub proposer: Option<Address>,
 }
@@ -29,14 +29,16 @@ use aptos_types::{
 };
 use aptos_vm::move_vm_ext::MoveResolverExt;
 use move_deps::{
-    move_binary_format::file_format::FunctionHandleIndex,
+    move_binary_format::file_format::{
+        FunctionHandleIndex, SignatureToken, StructFieldInformation,
+    },
     move_core_types,
     move_core_types::{
         identifier::Identifier,
         language_storage::{ModuleId, StructTag, TypeTag},
         value::{MoveStructLayout, MoveTypeLayout},
     },
-    move_resource_viewer::MoveValueAnnotator,
+    move_resource_viewer::{AnnotatedMoveStruct, MoveValueAnnotator},
 };
 use serde_json::Value;
 use std::sync::Arc;
@@ -80,6 +82,41 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         self.inner.move_struct_fields(typ, bytes)
     }
 
+    pub fn view_resource<'b>(
+        &self,
+        typ: &StructTag,
+        bytes: &'b [u8],
+    ) -> Result<AnnotatedMoveStruct> {
+        self.inner.view_resource(typ, bytes)
+    }
+
+    /// Whether the struct `typ` is declared the way resource groups are: a non generic struct
+    /// with `key` and no fields of its own, its members being stored in its slot instead. The
+    /// compiler gives structs declared without fields a single `dummy_field: bool`.
+    pub fn is_resource_group(&self, typ: &StructTag) -> Result<bool> {
+        let module = self.inner.get_module(&typ.module_id())?;
+        let def = module
+            .struct_defs
+            .iter()
+            .find(|def| {
+                let handle = module.struct_handle_at(def.struct_handle);
+                module.identifier_at(handle.name) == typ.name.as_ident_str()
+            })
+            .ok_or_else(|| format_err!("could not find struct {}", typ))?;
+        let handle = module.struct_handle_at(def.struct_handle);
+        let fields = match &def.field_information {
+            StructFieldInformation::Native => return Ok(false),
+            StructFieldInformation::Declared(fields) => fields,
+        };
+        Ok(handle.abilities.has_key()
+            && handle.type_parameters.is_empty()
+            && matches!(
+                fields.as_slice(),
+                [field] if module.identifier_at(field.name).as_str() == "dummy_field"
+                    && field.signature.0 == SignatureToken::Bool
+            ))
+    }
+
     pub fn try_into_pending_transaction(&self, txn: SignedTransaction) -> Result<Transaction> {
         let payload = self.try_into_transaction_payload(txn.payload().clone())?;
         Ok((txn, payload).into())
@@ -18,11 +18,110 @@ pub struct ApiConfig {
     // optional for compatible with old configuration
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content_length_limit: Option<u64>,
+    // optional per-route overrides of `content_length_limit`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_content_length_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submit_transaction_content_length_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simulate_transaction_content_length_limit: Option<u64>,
+    // interval at which committed transactions are polled for when waiting on a submission
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_poll_interval_ms: Option<u64>,
+    // storage reads taking longer than this are logged as slow queries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slow_query_threshold_ms: Option<u64>,
+    // How long to wait for mempool to acknowledge a submitted transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mempool_submission_timeout_ms: Option<u64>,
+    // Consecutive mempool submission failures after which submissions are fast-failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mempool_circuit_breaker_failure_threshold: Option<u64>,
+    // How long submissions are fast-failed before mempool is probed again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mempool_circuit_breaker_cooldown_ms: Option<u64>,
+    // number of transactions cached for lookups by hash, 0 disables the cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_by_hash_cache_capacity: Option<u64>,
+    // whether the health check also probes mempool, for fullnodes serving transaction submission
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_check_mempool_probe: Option<bool>,
+    // how long the health check waits for mempool to answer the probe
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_check_mempool_timeout_ms: Option<u64>,
+    // How long heavy reads may run before the request is failed with a timeout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_read_duration_ms: Option<u64>,
+    // How often mempool queue depth is polled for the API metrics
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mempool_status_poll_interval_ms: Option<u64>,
+    // Threads used to convert the transactions of an account transactions page concurrently
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_conversion_threads: Option<u64>,
+    // number of recent blocks whose info is cached, 0 disables the cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_info_cache_capacity: Option<u64>,
+    // heavy reads (transaction pages, prefix scans) served at once, more are rejected as overloaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_heavy_reads: Option<u64>,
+    // most events a single events read returns, larger limits are clamped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_events_page_size: Option<u16>,
+    // most transactions a single transactions read returns, larger limits are clamped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_transactions_page_size: Option<u16>,
+    // most transactions a single account transactions read returns, larger limits are clamped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_account_transactions_page_size: Option<u16>,
+    // number of versions whose block timestamp is cached, 0 disables the cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_timestamp_cache_capacity: Option<u64>,
+    // how long the latest ledger info is served from cache, 0 disables the cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ledger_info_cache_ttl_ms: Option<u64>,
+    // whether a block height that fails to parse is derived by counting blocks instead, which is slow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_height_fallback_enabled: Option<bool>,
+    // number of entries of each kind of immutable DB read cached in front of the DB, 0 disables the cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db_read_cache_capacity: Option<u64>,
+    // number of addresses remembered as existing by needs_account_creation, 0 disables the cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub existing_accounts_cache_capacity: Option<u64>,
+    // maximum number of state values read for an account at once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_account_state_values: Option<u64>,
+    // estimated size over which transaction pages are rejected, 0 disables the check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_transactions_response_bytes: Option<u64>,
 }
 
 pub const DEFAULT_ADDRESS: &str = "127.0.0.1";
 pub const DEFAULT_PORT: u16 = 8080;
 pub const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 4 * 1024 * 1024; // 4mb
+pub const DEFAULT_TRANSACTION_POLL_INTERVAL_MS: u64 = 100;
+pub const DEFAULT_SLOW_QUERY_THRESHOLD_MS: u64 = 1000;
+pub const DEFAULT_MEMPOOL_SUBMISSION_TIMEOUT_MS: u64 = 10_000;
+pub const DEFAULT_MEMPOOL_CIRCUIT_BREAKER_FAILURE_THRESHOLD: u64 = 5;
+pub const DEFAULT_MEMPOOL_CIRCUIT_BREAKER_COOLDOWN_MS: u64 = 5_000;
+pub const DEFAULT_TRANSACTION_BY_HASH_CACHE_CAPACITY: u64 = 0;
+pub const DEFAULT_HEALTH_CHECK_MEMPOOL_PROBE: bool = false;
+pub const DEFAULT_HEALTH_CHECK_MEMPOOL_TIMEOUT_MS: u64 = 1_000;
+pub const DEFAULT_MAX_READ_DURATION_MS: u64 = 30_000;
+pub const DEFAULT_MEMPOOL_STATUS_POLL_INTERVAL_MS: u64 = 10_000;
+pub const DEFAULT_TRANSACTION_CONVERSION_THREADS: u64 = 4;
+pub const DEFAULT_BLOCK_INFO_CACHE_CAPACITY: u64 = 100;
+pub const DEFAULT_MAX_CONCURRENT_HEAVY_READS: u64 = 64;
+pub const DEFAULT_MAX_EVENTS_PAGE_SIZE: u16 = 1000;
+pub const DEFAULT_MAX_TRANSACTIONS_PAGE_SIZE: u16 = 1000;
+pub const DEFAULT_MAX_ACCOUNT_TRANSACTIONS_PAGE_SIZE: u16 = 1000;
+pub const DEFAULT_BLOCK_TIMESTAMP_CACHE_CAPACITY: u64 = 1000;
+pub const DEFAULT_LEDGER_INFO_CACHE_TTL_MS: u64 = 0;
+pub const DEFAULT_BLOCK_HEIGHT_FALLBACK_ENABLED: bool = false;
+pub const DEFAULT_DB_READ_CACHE_CAPACITY: u64 = 0;
+pub const DEFAULT_EXISTING_ACCOUNTS_CACHE_CAPACITY: u64 = 10000;
+pub const DEFAULT_MAX_ACCOUNT_STATE_VALUES: u64 = 10_000;
+pub const DEFAULT_MAX_TRANSACTIONS_RESPONSE_BYTES: u64 = 0;
 
 fn default_enabled() -> bool {
     true
@@ -38,6 +137,32 @@ impl Default for ApiConfig {
             tls_cert_path: None,
             tls_key_path: None,
             content_length_limit: None,
+            read_content_length_limit: None,
+            submit_transaction_content_length_limit: None,
+            simulate_transaction_content_length_limit: None,
+            transaction_poll_interval_ms: None,
+            slow_query_threshold_ms: None,
+            mempool_submission_timeout_ms: None,
+            mempool_circuit_breaker_failure_threshold: None,
+            mempool_circuit_breaker_cooldown_ms: None,
+            transaction_by_hash_cache_capacity: None,
+            health_check_mempool_probe: None,
+            health_check_mempool_timeout_ms: None,
+            max_read_duration_ms: None,
+            mempool_status_poll_interval_ms: None,
+            transaction_conversion_threads: None,
+            block_info_cache_capacity: None,
+            max_concurrent_heavy_reads: None,
+            max_events_page_size: None,
+            max_transactions_page_size: None,
+            max_account_transactions_page_size: None,
+            block_timestamp_cache_capacity: None,
+            ledger_info_cache_ttl_ms: None,
+            block_height_fallback_enabled: None,
+            db_read_cache_capacity: None,
+            existing_accounts_cache_capacity: None,
+            max_account_state_values: None,
+            max_transactions_response_bytes: None,
         }
     }
 }
@@ -53,4 +178,134 @@ impl ApiConfig {
             None => DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT,
         }
     }
+
+    pub fn read_content_length_limit(&self) -> u64 {
+        self.read_content_length_limit
+            .unwrap_or_else(|| self.content_length_limit())
+    }
+
+    pub fn submit_transaction_content_length_limit(&self) -> u64 {
+        self.submit_transaction_content_length_limit
+            .unwrap_or_else(|| self.content_length_limit())
+    }
+
+    pub fn simulate_transaction_content_length_limit(&self) -> u64 {
+        self.simulate_transaction_content_length_limit
+            .unwrap_or_else(|| self.content_length_limit())
+    }
+
+    pub fn transaction_poll_interval_ms(&self) -> u64 {
+        self.transaction_poll_interval_ms
+            .unwrap_or(DEFAULT_TRANSACTION_POLL_INTERVAL_MS)
+    }
+
+    pub fn slow_query_threshold_ms(&self) -> u64 {
+        self.slow_query_threshold_ms
+            .unwrap_or(DEFAULT_SLOW_QUERY_THRESHOLD_MS)
+    }
+
+    pub fn mempool_submission_timeout_ms(&self) -> u64 {
+        self.mempool_submission_timeout_ms
+            .unwrap_or(DEFAULT_MEMPOOL_SUBMISSION_TIMEOUT_MS)
+    }
+
+    pub fn mempool_circuit_breaker_failure_threshold(&self) -> u64 {
+        self.mempool_circuit_breaker_failure_threshold
+            .unwrap_or(DEFAULT_MEMPOOL_CIRCUIT_BREAKER_FAILURE_THRESHOLD)
+    }
+
+    pub fn mempool_circuit_breaker_cooldown_ms(&self) -> u64 {
+        self.mempool_circuit_breaker_cooldown_ms
+            .unwrap_or(DEFAULT_MEMPOOL_CIRCUIT_BREAKER_COOLDOWN_MS)
+    }
+
+    pub fn transaction_by_hash_cache_capacity(&self) -> u64 {
+        self.transaction_by_hash_cache_capacity
+            .unwrap_or(DEFAULT_TRANSACTION_BY_HASH_CACHE_CAPACITY)
+    }
+
+    pub fn health_check_mempool_probe(&self) -> bool {
+        self.health_check_mempool_probe
+            .unwrap_or(DEFAULT_HEALTH_CHECK_MEMPOOL_PROBE)
+    }
+
+    pub fn health_check_mempool_timeout_ms(&self) -> u64 {
+        self.health_check_mempool_timeout_ms
+            .unwrap_or(DEFAULT_HEALTH_CHECK_MEMPOOL_TIMEOUT_MS)
+    }
+
+    pub fn max_read_duration_ms(&self) -> u64 {
+        self.max_read_duration_ms
+            .unwrap_or(DEFAULT_MAX_READ_DURATION_MS)
+    }
+
+    pub fn mempool_status_poll_interval_ms(&self) -> u64 {
+        self.mempool_status_poll_interval_ms
+            .unwrap_or(DEFAULT_MEMPOOL_STATUS_POLL_INTERVAL_MS)
+    }
+
+    pub fn transaction_conversion_threads(&self) -> u64 {
+        self.transaction_conversion_threads
+            .unwrap_or(DEFAULT_TRANSACTION_CONVERSION_THREADS)
+    }
+
+    pub fn block_info_cache_capacity(&self) -> u64 {
+        self.block_info_cache_capacity
+            .unwrap_or(DEFAULT_BLOCK_INFO_CACHE_CAPACITY)
+    }
+
+    pub fn max_concurrent_heavy_reads(&self) -> u64 {
+        self.max_concurrent_heavy_reads
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_HEAVY_READS)
+    }
+
+    pub fn max_events_page_size(&self) -> u16 {
+        self.max_events_page_size
+            .unwrap_or(DEFAULT_MAX_EVENTS_PAGE_SIZE)
+    }
+
+    pub fn max_transactions_page_size(&self) -> u16 {
+        self.max_transactions_page_size
+            .unwrap_or(DEFAULT_MAX_TRANSACTIONS_PAGE_SIZE)
+    }
+
+    pub fn max_account_transactions_page_size(&self) -> u16 {
+        self.max_account_transactions_page_size
+            .unwrap_or(DEFAULT_MAX_ACCOUNT_TRANSACTIONS_PAGE_SIZE)
+    }
+
+    pub fn block_timestamp_cache_capacity(&self) -> u64 {
+        self.block_timestamp_cache_capacity
+            .unwrap_or(DEFAULT_BLOCK_TIMESTAMP_CACHE_CAPACITY)
+    }
+
+    pub fn ledger_info_cache_ttl_ms(&self) -> u64 {
+        self.ledger_info_cache_ttl_ms
+            .unwrap_or(DEFAULT_LEDGER_INFO_CACHE_TTL_MS)
+    }
+
+    pub fn block_height_fallback_enabled(&self) -> bool {
+        self.block_height_fallback_enabled
+            .unwrap_or(DEFAULT_BLOCK_HEIGHT_FALLBACK_ENABLED)
+    }
+
+    pub fn db_read_cache_capacity(&self) -> u64 {
+        self.db_read_cache_capacity
+            .unwrap_or(DEFAULT_DB_READ_CACHE_CAPACITY)
+    }
+
+    pub fn existing_accounts_cache_capacity(&self) -> u64 {
+        self.existing_accounts_cache_capacity
+            .unwrap_or(DEFAULT_EXISTING_ACCOUNTS_CACHE_CAPACITY)
+    }
+
+    pub fn max_account_state_values(&self) -> u64 {
+        self.max_account_state_values
+            .unwrap_or(DEFAULT_MAX_ACCOUNT_STATE_VALUES)
+    }
+
+    pub fn max_transactions_response_bytes(&self) -> u64 {
+        self.max_transactions_response_bytes
+            .unwrap_or(DEFAULT_MAX_TRANSACTIONS_RESPONSE_BYTES)
+    }
 }
@@ -151,6 +151,8 @@ impl BlockCache {
             start_version: 0,
             end_version: 0,
             num_transactions: 1,
+            num_user_transactions: 0,
+            proposer: None,
         };
         blocks.insert(0, genesis_block_info);
         hashes.insert(genesis_block_info.block_hash, 0);
@@ -12,6 +12,7 @@ use crate::{
     },
     counters,
     logging::{LogEntry, LogSchema, TxnsLog},
+    shared_mempool::types::MempoolQueueStatus,
 };
 use aptos_config::config::NodeConfig;
 use aptos_crypto::HashValue;
@@ -294,6 +295,13 @@ impl Mempool {
         self.transactions.timeline_range(start_id, end_id)
     }
 
+    pub fn queue_status(&self) -> MempoolQueueStatus {
+        MempoolQueueStatus {
+            size: self.transactions.size(),
+            capacity: self.transactions.capacity(),
+        }
+    }
+
     pub fn gen_snapshot(&self) -> TxnsLog {
         self.transactions.gen_snapshot(&self.metrics_cache)
     }
@@ -556,6 +556,15 @@ impl TransactionStore {
         self.track_indices();
     }
 
+    /// Number of transactions in the store, as counted against its capacity
+    pub(crate) fn size(&self) -> usize {
+        self.system_ttl_index.size()
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
     pub(crate) fn iter_queue(&self) -> PriorityQueueIter {
         self.priority_index.iter()
     }
@@ -148,6 +148,13 @@ async fn handle_client_request<V>(
                 ))
                 .await;
         }
+        MempoolClientRequest::GetQueueStatus(callback) => {
+            // Only reads two counters under the lock, so there's no need to spawn a task
+            let status = smp.mempool.lock().queue_status();
+            if callback.send(status).is_err() {
+                counters::CLIENT_CALLBACK_FAIL.inc();
+            }
+        }
     }
 }
 
@@ -208,6 +208,16 @@ pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 pub enum MempoolClientRequest {
     SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
     GetTransactionByHash(HashValue, oneshot::Sender<Option<SignedTransaction>>),
+    GetQueueStatus(oneshot::Sender<MempoolQueueStatus>),
+}
+
+/// How full mempool is, for watching backpressure on submissions
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MempoolQueueStatus {
+    /// Number of transactions in mempool
+    pub size: usize,
+    /// Number of transactions mempool holds before rejecting new ones
+    pub capacity: usize,
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
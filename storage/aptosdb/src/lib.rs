@@ -66,7 +66,7 @@ use aptos_logger::prelude::*;
 use aptos_types::state_store::table::{TableHandle, TableInfo};
 use aptos_types::{
     account_address::AccountAddress,
-    contract_event::EventWithVersion,
+    contract_event::{ContractEvent, EventWithVersion},
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
     event::EventKey,
@@ -847,6 +847,32 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_state_values_by_key_prefix_from(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        version: Version,
+        limit: usize,
+    ) -> Result<Vec<(StateKey, StateValue)>> {
+        gauged_api("get_state_values_by_key_prefix_from", || {
+            self.state_store
+                .get_values_by_key_prefix_from(key_prefix, start_key, version, limit)
+        })
+    }
+
+    fn get_state_values_with_versions_by_key_prefix_from(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        version: Version,
+        limit: usize,
+    ) -> Result<Vec<(StateKey, Version, StateValue)>> {
+        gauged_api("get_state_values_with_versions_by_key_prefix_from", || {
+            self.state_store
+                .get_values_with_versions_by_key_prefix_from(key_prefix, start_key, version, limit)
+        })
+    }
+
     fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
         gauged_api("get_latest_ledger_info_option", || {
             Ok(self.ledger_store.get_latest_ledger_info_option())
@@ -914,6 +940,24 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_transaction_versions_by_hashes(
+        &self,
+        hashes: &[HashValue],
+        ledger_version: Version,
+    ) -> Result<Vec<Option<Version>>> {
+        gauged_api("get_transaction_versions_by_hashes", || {
+            error_if_too_many_requested(hashes.len() as u64, MAX_LIMIT)?;
+
+            hashes
+                .iter()
+                .map(|hash| {
+                    self.transaction_store
+                        .get_transaction_version_by_hash(hash, ledger_version)
+                })
+                .collect()
+        })
+    }
+
     /// Returns the transaction by version, delegates to `AptosDB::get_transaction_with_proof`.
     /// Returns an error if the provided version is not found.
     fn get_transaction_by_version(
@@ -990,6 +1034,109 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_transactions_without_proof(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Vec<(Transaction, TransactionInfo, Vec<ContractEvent>, WriteSet)>> {
+        gauged_api("get_transactions_without_proof", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+            if start_version > ledger_version || limit == 0 {
+                return Ok(vec![]);
+            }
+
+            error_if_version_is_pruned(
+                &self.pruner,
+                PrunerIndex::LedgerPrunerIndex,
+                "Transaction",
+                start_version,
+            )?;
+
+            let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+            (start_version..start_version + limit)
+                .map(|version| {
+                    let txn = self.transaction_store.get_transaction(version)?;
+                    let txn_info = self.ledger_store.get_transaction_info(version)?;
+                    let events = if fetch_events {
+                        self.event_store.get_events_by_version(version)?
+                    } else {
+                        vec![]
+                    };
+                    let write_set = self.transaction_store.get_write_set(version)?;
+                    Ok((txn, txn_info, events, write_set))
+                })
+                .collect()
+        })
+    }
+
+    fn get_transactions_by_versions(
+        &self,
+        versions: &[Version],
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Vec<(Transaction, TransactionInfo, Vec<ContractEvent>, WriteSet)>> {
+        gauged_api("get_transactions_by_versions", || {
+            error_if_too_many_requested(versions.len() as u64, MAX_LIMIT)?;
+
+            versions
+                .iter()
+                .map(|&version| {
+                    ensure!(
+                        version <= ledger_version,
+                        "Version {} is after ledger version {}",
+                        version,
+                        ledger_version
+                    );
+                    error_if_version_is_pruned(
+                        &self.pruner,
+                        PrunerIndex::LedgerPrunerIndex,
+                        "Transaction",
+                        version,
+                    )?;
+                    let txn = self.transaction_store.get_transaction(version)?;
+                    let txn_info = self.ledger_store.get_transaction_info(version)?;
+                    let events = if fetch_events {
+                        self.event_store.get_events_by_version(version)?
+                    } else {
+                        vec![]
+                    };
+                    let write_set = self.transaction_store.get_write_set(version)?;
+                    Ok((txn, txn_info, events, write_set))
+                })
+                .collect()
+        })
+    }
+
+    fn get_transaction_infos(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<TransactionInfo>> {
+        gauged_api("get_transaction_infos", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+            if start_version > ledger_version || limit == 0 {
+                return Ok(vec![]);
+            }
+
+            error_if_version_is_pruned(
+                &self.pruner,
+                PrunerIndex::LedgerPrunerIndex,
+                "Transaction",
+                start_version,
+            )?;
+
+            let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+            self.ledger_store
+                .get_transaction_info_iter(start_version, limit as usize)?
+                .collect()
+        })
+    }
+
     /// Get the first version that txn starts existent.
     fn get_first_txn_version(&self) -> Result<Option<Version>> {
         gauged_api("get_first_txn_version", || {
@@ -1169,6 +1316,24 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_state_value_with_version_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<(Version, StateValue)>> {
+        gauged_api("get_state_value_with_version_by_version", || {
+            error_if_version_is_pruned(
+                &self.pruner,
+                PrunerIndex::StateStorePrunerIndex,
+                "State",
+                version,
+            )?;
+
+            self.state_store
+                .get_state_value_with_version_by_version(state_key, version)
+        })
+    }
+
     /// Returns the proof of the given state key and version.
     fn get_state_proof_by_version(
         &self,
@@ -112,6 +112,23 @@ impl DbReader for StateDb {
             .transpose()
     }
 
+    /// Same as `get_state_value_by_version`, along with the version the value was written at.
+    fn get_state_value_with_version_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<(Version, StateValue)>> {
+        let mut read_opts = ReadOptions::default();
+        // We want `None` if the state_key changes in iteration.
+        read_opts.set_prefix_same_as_start(true);
+        let mut iter = self.ledger_db.iter::<StateValueSchema>(read_opts)?;
+        iter.seek(&(state_key.clone(), version))?;
+        iter.next()
+            .transpose()?
+            .map(|((_, version), state_value)| Ok((version, state_value)))
+            .transpose()
+    }
+
     /// Returns the proof of the given state key and version.
     fn get_state_proof_by_version(
         &self,
@@ -159,6 +176,15 @@ impl DbReader for StateStore {
         self.deref().get_state_value_by_version(state_key, version)
     }
 
+    fn get_state_value_with_version_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<(Version, StateValue)>> {
+        self.deref()
+            .get_state_value_with_version_by_version(state_key, version)
+    }
+
     /// Returns the proof of the given state key and version.
     fn get_state_proof_by_version(
         &self,
@@ -343,6 +369,90 @@ impl StateStore {
         key_prefix: &StateKeyPrefix,
         desired_version: Version,
     ) -> Result<HashMap<StateKey, StateValue>> {
+        let mut result = HashMap::new();
+        self.scan_values_by_key_prefix(
+            key_prefix,
+            None,
+            desired_version,
+            |state_key, _version, state_value| {
+                result.insert(state_key, state_value);
+                // We don't allow fetching arbitrarily large number of values to be fetched as this can
+                // potentially slowdown the DB.
+                if result.len() > MAX_VALUES_TO_FETCH_FOR_KEY_PREFIX {
+                    return Err(anyhow!(
+                        "Too many values requested for key_prefix {:?} - maximum allowed {:?}",
+                        key_prefix,
+                        MAX_VALUES_TO_FETCH_FOR_KEY_PREFIX
+                    ));
+                }
+                Ok(true)
+            },
+        )?;
+        Ok(result)
+    }
+
+    /// Returns at most `limit` key, value pairs for a particular state key prefix at desired
+    /// version, in the order the keys are stored, starting from `start_key` if given. Only the
+    /// values returned are read, so this can page through a prefix with any number of keys by
+    /// passing the key after the last one of the previous page as `start_key`.
+    pub fn get_values_by_key_prefix_from(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        desired_version: Version,
+        limit: usize,
+    ) -> Result<Vec<(StateKey, StateValue)>> {
+        let mut result = Vec::new();
+        if limit == 0 {
+            return Ok(result);
+        }
+        self.scan_values_by_key_prefix(
+            key_prefix,
+            start_key,
+            desired_version,
+            |state_key, _version, state_value| {
+                result.push((state_key, state_value));
+                Ok(result.len() < limit)
+            },
+        )?;
+        Ok(result)
+    }
+
+    /// Same as `get_values_by_key_prefix_from`, along with the version each value was written at,
+    /// which comes with the key the value is stored under.
+    pub fn get_values_with_versions_by_key_prefix_from(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        desired_version: Version,
+        limit: usize,
+    ) -> Result<Vec<(StateKey, Version, StateValue)>> {
+        let mut result = Vec::new();
+        if limit == 0 {
+            return Ok(result);
+        }
+        self.scan_values_by_key_prefix(
+            key_prefix,
+            start_key,
+            desired_version,
+            |state_key, version, state_value| {
+                result.push((state_key, version, state_value));
+                Ok(result.len() < limit)
+            },
+        )?;
+        Ok(result)
+    }
+
+    /// Visits the keys with the given prefix in storage order, starting from `start_key` if
+    /// given, passing each one with its value at desired version, and the version that value was
+    /// written at, to `visit` until it returns false.
+    fn scan_values_by_key_prefix(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        desired_version: Version,
+        mut visit: impl FnMut(StateKey, Version, StateValue) -> Result<bool>,
+    ) -> Result<()> {
         let mut read_opts = ReadOptions::default();
         // Without this, iterators are not guaranteed a total order of all keys, but only keys for the same prefix.
         // For example,
@@ -354,9 +464,21 @@ impl StateStore {
         // keys starting with `aptos/abc`.
         read_opts.set_total_order_seek(true);
         let mut iter = self.ledger_db.iter::<StateValueSchema>(read_opts)?;
-        let mut result = HashMap::new();
         let mut prev_key = None;
-        iter.seek(&(key_prefix))?;
+        match start_key {
+            Some(start_key) => {
+                ensure!(
+                    key_prefix.is_prefix(start_key)?,
+                    "Start key {:?} doesn't have key_prefix {:?}",
+                    start_key,
+                    key_prefix
+                );
+                // Lands on the start key at the latest version up to the desired one, or on the
+                // next key if it has none.
+                iter.seek(&(start_key.clone(), desired_version))?;
+            }
+            None => iter.seek(&(key_prefix))?,
+        }
         while let Some(((state_key, version), state_value)) = iter.next().transpose()? {
             // In case the previous seek() ends on the same key with version 0.
             if Some(&state_key) == prev_key.as_ref() {
@@ -374,21 +496,14 @@ impl StateStore {
                 continue;
             }
 
-            result.insert(state_key.clone(), state_value);
-            // We don't allow fetching arbitrarily large number of values to be fetched as this can
-            // potentially slowdown the DB.
-            if result.len() > MAX_VALUES_TO_FETCH_FOR_KEY_PREFIX {
-                return Err(anyhow!(
-                    "Too many values requested for key_prefix {:?} - maximum allowed {:?}",
-                    key_prefix,
-                    MAX_VALUES_TO_FETCH_FOR_KEY_PREFIX
-                ));
+            if !visit(state_key.clone(), version, state_value)? {
+                break;
             }
             prev_key = Some(state_key.clone());
             // Seek to the next key - this can be done by seeking to the current key with version 0
             iter.seek(&(state_key, 0))?;
         }
-        Ok(result)
+        Ok(())
     }
 
     /// Gets the proof that proves a range of accounts.
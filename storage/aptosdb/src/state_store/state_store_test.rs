@@ -235,6 +235,69 @@ fn test_get_values_by_key_prefix() {
     assert_eq!(*key_value_map.get(&key5).unwrap(), value5_v2);
 }
 
+#[test]
+fn test_get_values_by_key_prefix_from() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let address = AccountAddress::new([12u8; AccountAddress::LENGTH]);
+    let key_prefix = StateKeyPrefix::from(address);
+
+    let keys: Vec<_> = (0..5)
+        .map(|i| StateKey::AccessPath(AccessPath::new(address, format!("state_key{}", i).into())))
+        .collect();
+    put_value_set(
+        store,
+        keys.iter()
+            .map(|key| (key.clone(), StateValue::from(b"v0".to_vec())))
+            .collect(),
+        0,
+        None,
+    );
+    put_value_set(
+        store,
+        vec![(keys[1].clone(), StateValue::from(b"v1".to_vec()))],
+        1,
+        Some(0),
+    );
+
+    // Pages of two, each starting from the key after the previous page
+    let mut paged = vec![];
+    let mut start_key = None;
+    loop {
+        let mut page = store
+            .get_values_by_key_prefix_from(&key_prefix, start_key.as_ref(), 0, 3)
+            .unwrap();
+        start_key = if page.len() == 3 {
+            page.pop().map(|(key, _)| key)
+        } else {
+            None
+        };
+        paged.extend(page);
+        if start_key.is_none() {
+            break;
+        }
+    }
+    assert_eq!(paged.len(), keys.len());
+    assert!(paged
+        .iter()
+        .all(|(_, value)| *value == StateValue::from(b"v0".to_vec())));
+
+    // A start key is read at the desired version too
+    let page = store
+        .get_values_by_key_prefix_from(&key_prefix, Some(&keys[1]), 1, 1)
+        .unwrap();
+    assert_eq!(
+        page,
+        vec![(keys[1].clone(), StateValue::from(b"v1".to_vec()))]
+    );
+
+    let other_address = AccountAddress::new([22u8; AccountAddress::LENGTH]);
+    assert!(store
+        .get_values_by_key_prefix_from(&StateKeyPrefix::from(other_address), Some(&keys[0]), 0, 1)
+        .is_err());
+}
+
 #[test]
 fn test_stale_node_index() {
     let key1 = StateKey::Raw(String::from("test_key1").into_bytes());
@@ -8,7 +8,7 @@ use aptos_types::{
     access_path::AccessPath,
     account_address::AccountAddress,
     account_config::CORE_CODE_ADDRESS,
-    contract_event::EventWithVersion,
+    contract_event::{ContractEvent, EventWithVersion},
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
     event::EventKey,
@@ -27,7 +27,7 @@ use aptos_types::{
         state_value::{StateValue, StateValueChunkWithProof},
     },
     transaction::{
-        AccountTransactionsWithProof, TransactionInfo, TransactionListWithProof,
+        AccountTransactionsWithProof, Transaction, TransactionInfo, TransactionListWithProof,
         TransactionOutputListWithProof, TransactionToCommit, TransactionWithProof, Version,
     },
     write_set::WriteSet,
@@ -184,6 +184,41 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns at most `limit` transactions starting at `start_version`, with their infos, events
+    /// and write sets, but none of the proofs `get_transaction_outputs` comes with. Events are
+    /// only read if `fetch_events`, otherwise they're left empty.
+    fn get_transactions_without_proof(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Vec<(Transaction, TransactionInfo, Vec<ContractEvent>, WriteSet)>> {
+        unimplemented!()
+    }
+
+    /// Same as `get_transactions_without_proof`, but for the given versions, which don't need to
+    /// be adjacent, in the order given. Fails if any of them is after `ledger_version`.
+    fn get_transactions_by_versions(
+        &self,
+        versions: &[Version],
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Vec<(Transaction, TransactionInfo, Vec<ContractEvent>, WriteSet)>> {
+        unimplemented!()
+    }
+
+    /// Returns the infos of at most `limit` transactions starting at `start_version`, without
+    /// reading the transactions, their events or their write sets.
+    fn get_transaction_infos(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<TransactionInfo>> {
+        unimplemented!()
+    }
+
     /// See [AptosDB::get_transaction_by_hash].
     ///
     /// [AptosDB::get_transaction_by_hash]: ../aptosdb/struct.AptosDB.html#method.get_transaction_by_hash
@@ -196,6 +231,16 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Looks up the versions several transactions were committed at, in the order of `hashes`,
+    /// with `None` for the ones not committed as of `ledger_version`.
+    fn get_transaction_versions_by_hashes(
+        &self,
+        hashes: &[HashValue],
+        ledger_version: Version,
+    ) -> Result<Vec<Option<Version>>> {
+        unimplemented!()
+    }
+
     /// See [AptosDB::get_transaction_by_version].
     ///
     /// [AptosDB::get_transaction_by_version]: ../aptosdb/struct.AptosDB.html#method.get_transaction_by_version
@@ -307,6 +352,32 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns at most `limit` key, value pairs for a particular state key prefix at desired
+    /// version, in storage order, starting from `start_key` if given. Unlike
+    /// `get_state_values_by_key_prefix` it doesn't read the whole prefix, so it can page through
+    /// prefixes of any size.
+    fn get_state_values_by_key_prefix_from(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        version: Version,
+        limit: usize,
+    ) -> Result<Vec<(StateKey, StateValue)>> {
+        unimplemented!()
+    }
+
+    /// Same as `get_state_values_by_key_prefix_from`, along with the version each value was
+    /// written at.
+    fn get_state_values_with_versions_by_key_prefix_from(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        version: Version,
+        limit: usize,
+    ) -> Result<Vec<(StateKey, Version, StateValue)>> {
+        unimplemented!()
+    }
+
     /// Returns the latest ledger info, if any.
     fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
         unimplemented!()
@@ -414,6 +485,15 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Same as `get_state_value_by_version`, along with the version the value was written at.
+    fn get_state_value_with_version_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<(Version, StateValue)>> {
+        unimplemented!()
+    }
+
     /// Returns the proof of the given state key and version.
     fn get_state_proof_by_version(
         &self,